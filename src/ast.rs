@@ -1,7 +1,9 @@
 use crate::error::Res;
-use crate::token;
+use crate::model::Model;
+use crate::symbol::Symbol;
+use crate::token::{self, Span};
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[cfg(test)]
 mod test;
@@ -10,6 +12,7 @@ mod test;
 pub struct Unary {
     operator: token::Kind,
     right: Box<Formula>,
+    span: Span,
 }
 
 impl Unary {
@@ -22,6 +25,9 @@ impl Unary {
     pub fn right(&self) -> &Formula {
         &self.right
     }
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -29,28 +35,39 @@ pub struct Binary {
     operator: token::Kind,
     left: Box<Formula>,
     right: Box<Formula>,
+    span: Span,
 }
 
 impl Binary {
-    pub fn parts<'a>(&'a self) -> (&'a Formula, token::Kind, &'a Formula) {
+    pub fn parts(&self) -> (&Formula, token::Kind, &Formula) {
         (self.left.as_ref(), self.operator, self.right.as_ref())
     }
     pub fn destroy(self) -> (Formula, token::Kind, Formula) {
         (*self.left, self.operator, *self.right)
     }
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Leaf {
-    ident: Rc<str>,
+    ident: Symbol,
+    span: Span,
 }
 
 impl Leaf {
-    pub fn destroy(self) -> Rc<str> {
+    pub fn destroy(self) -> Symbol {
         self.ident
     }
-    pub fn string(&self) -> Rc<str> {
-        Rc::clone(&self.ident)
+    pub fn symbol(&self) -> Symbol {
+        self.ident
+    }
+    pub fn string(&self) -> Arc<str> {
+        self.ident.name()
+    }
+    pub fn span(&self) -> Span {
+        self.span
     }
 }
 
@@ -62,66 +79,138 @@ pub enum Formula {
     // Link(context::InnerContext), // @todo? It could be faster to compose formulas
 }
 
-pub enum Statement {
+/// a formula after the simplification: a constant has no formula
+#[derive(Debug, Clone)]
+pub enum Simplified {
+    Constant(bool),
     Formula(Formula),
-    Eoi,
-    Exit,
-    Help,
-    Execute,
-    Query,
-    Delete(usize),
 }
 
-impl From<Formula> for Statement {
-    fn from(f: Formula) -> Statement {
-        Statement::Formula(f)
+impl fmt::Display for Simplified {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Simplified::Constant(value) => write!(f, "{value}"),
+            Simplified::Formula(formula) => write!(f, "{formula}"),
+        }
+    }
+}
+
+/// the normal forms printed by `nnf`, `cnf` and `dnf`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalForm {
+    Negation,
+    Conjunctive,
+    Disjunctive,
+}
+
+impl NormalForm {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NormalForm::Negation => "NNF",
+            NormalForm::Conjunctive => "CNF",
+            NormalForm::Disjunctive => "DNF",
+        }
     }
 }
 
-impl fmt::Display for Statement {
+impl fmt::Display for NormalForm {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Statement::Formula(f) => format!("{f}"),
-                Statement::Execute => format!("EXECUTE"),
-                Statement::Query => format!("QUERY"),
-                Statement::Delete(n) => format!("DELETE {n}"),
-                Statement::Eoi => format!("END OF INPUT"),
-                Statement::Exit => format!("EXIT"),
-                Statement::Help => format!("HELP"),
-            }
-        )
+        write!(f, "{}", self.as_str())
     }
 }
 
 impl Formula {
-    pub fn new_leaf(ident: Rc<str>) -> Formula {
-        Formula::Leaf(Leaf { ident })
+    /// the span is empty, see `Formula::with_span()`
+    /// `Formula::new_leaf("a".into())` is the same atom as any parsed `a`
+    pub fn new_leaf(ident: Symbol) -> Formula {
+        Formula::Leaf(Leaf {
+            ident,
+            span: Span::default(),
+        })
     }
+    /// the span goes from the beginning of `left` to the end of `right`
     pub fn new_binary(left: Formula, operator: token::Kind, right: Formula) -> Formula {
         Formula::Binary(Binary {
+            span: left.span().join(right.span()),
             left: Box::new(left),
             operator,
             right: Box::new(right),
         })
     }
+    /// the span is the one of `right`, see `Formula::with_span()`
     pub fn new_unary(operator: token::Kind, right: Formula) -> Formula {
         Formula::Unary(Unary {
+            span: right.span(),
             operator,
             right: Box::new(right),
         })
     }
-    fn is_and(&self) -> bool {
+    /// where the formula comes from in the source (empty if it was not parsed)
+    pub fn span(&self) -> Span {
+        match self {
+            Formula::Unary(x) => x.span,
+            Formula::Binary(x) => x.span,
+            Formula::Leaf(x) => x.span,
+        }
+    }
+    /// sets the span of the outermost node
+    pub fn with_span(mut self, span: Span) -> Formula {
+        match &mut self {
+            Formula::Unary(x) => x.span = span,
+            Formula::Binary(x) => x.span = span,
+            Formula::Leaf(x) => x.span = span,
+        }
+        self
+    }
+    /// the value of the formula in `model`, None if a symbol is not assigned
+    pub fn eval(&self, model: &Model) -> Option<bool> {
+        Some(match self {
+            Formula::Leaf(x) => model.value(x.ident)?,
+            Formula::Unary(x) => !x.right.eval(model)?,
+            Formula::Binary(x) => {
+                let (l, r) = (x.left.eval(model)?, x.right.eval(model)?);
+                match x.operator {
+                    token::Kind::And => l && r,
+                    token::Kind::Or => l || r,
+                    token::Kind::Implies => !l || r,
+                    token::Kind::Equiv => l == r,
+                    _ => panic!("not a valid binary operator"),
+                }
+            }
+        })
+    }
+    /// the symbols of the formula, in the order they first appear
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        self.symbols_into(&mut symbols);
+        symbols
+    }
+    fn symbols_into(&self, symbols: &mut Vec<Symbol>) {
         match self {
-            Formula::Binary(x) => x.operator == token::Kind::And,
+            Formula::Leaf(x) => {
+                if !symbols.contains(&x.ident) {
+                    symbols.push(x.ident);
+                }
+            }
+            Formula::Unary(x) => x.right.symbols_into(symbols),
+            Formula::Binary(x) => {
+                x.left.symbols_into(symbols);
+                x.right.symbols_into(symbols);
+            }
+        }
+    }
+    fn is_binary(&self, operator: token::Kind) -> bool {
+        match self {
+            Formula::Binary(x) => x.operator == operator,
             _ => false,
         }
     }
 
-    /// push `!` inside (simplifying if repeated), and substitue `=>` and`<=>`
-    fn digest(self) -> Res<Formula> {
+    /// the negation normal form: push `!` inside (simplifying if repeated), and
+    /// substitue `=>` and`<=>`
+    /// every node keeps the span of the node it is derived from
+    pub fn digest(self) -> Res<Formula> {
+        let span = self.span();
         Ok(match self {
             Formula::Unary(x) => {
                 let (operator, right) = x.destroy();
@@ -159,15 +248,17 @@ impl Formula {
                 }
             }
             Formula::Leaf(_) => self,
-        })
+        }
+        .with_span(span))
     }
 
     fn negate_digest(self) -> Res<Formula> {
+        let span = self.span();
         Ok(match self {
             Formula::Unary(x) => {
                 let (operator, right) = x.destroy();
                 match operator {
-                    token::Kind::Not => right,
+                    token::Kind::Not => right.digest()?,
                     _ => panic!("not a valid unary operator"),
                 }
             }
@@ -199,31 +290,98 @@ impl Formula {
                 }
             }
             Formula::Leaf(_) => Formula::new_unary(token::Kind::Not, self),
-        })
+        }
+        .with_span(span))
     }
 
+    /// the conjunctive normal form: `|` distributed over `&`
     // don't call digest before distribute!
     pub fn distribute(self) -> Res<Formula> {
         let formula = self.digest()?;
-        formula.distribute_recurse()
+        formula.distribute_recurse(token::Kind::And)
     }
 
-    fn distribute_recurse(self) -> Res<Formula> {
+    /// the disjunctive normal form: `&` distributed over `|`
+    pub fn distribute_dnf(self) -> Res<Formula> {
+        let formula = self.digest()?;
+        formula.distribute_recurse(token::Kind::Or)
+    }
+
+    /// the formula in `form`: a clause of the CNF with both `x` and `~x` is
+    /// removed (a conjunct of the DNF too), so it can be `true` (`false`)
+    pub fn normal_form(self, form: NormalForm) -> Res<Simplified> {
+        let (formula, outer) = match form {
+            NormalForm::Negation => return Ok(Simplified::Formula(self.digest()?)),
+            NormalForm::Conjunctive => (self.distribute()?, token::Kind::And),
+            NormalForm::Disjunctive => (self.distribute_dnf()?, token::Kind::Or),
+        };
+        Ok(match formula.remove_complementary(outer) {
+            Some(formula) => Simplified::Formula(formula),
+            None => Simplified::Constant(outer == token::Kind::And),
+        })
+    }
+
+    /// removes the terms joined by `outer` with both `x` and `~x`, None if
+    /// there are none left
+    fn remove_complementary(self, outer: token::Kind) -> Option<Formula> {
+        let span = self.span();
+        match self {
+            Formula::Binary(x) if x.operator == outer => {
+                let (left, _, right) = x.destroy();
+                match (
+                    left.remove_complementary(outer),
+                    right.remove_complementary(outer),
+                ) {
+                    (Some(left), Some(right)) => {
+                        Some(Formula::new_binary(left, outer, right).with_span(span))
+                    }
+                    (formula, None) | (None, formula) => formula,
+                }
+            }
+            term => {
+                let mut literals = Vec::new();
+                term.literals(&mut literals);
+                let complementary = literals
+                    .iter()
+                    .any(|&(s, positive)| literals.contains(&(s, !positive)));
+                (!complementary).then_some(term)
+            }
+        }
+    }
+
+    /// the literals of a formula in NNF, with their sign
+    fn literals(&self, literals: &mut Vec<(Symbol, bool)>) {
+        match self {
+            Formula::Leaf(leaf) => literals.push((leaf.symbol(), true)),
+            Formula::Unary(x) => match x.right() {
+                Formula::Leaf(leaf) => literals.push((leaf.symbol(), false)),
+                right => right.literals(literals),
+            },
+            Formula::Binary(x) => {
+                x.left.literals(literals);
+                x.right.literals(literals);
+            }
+        }
+    }
+
+    /// `outer` is the operator left on top, the other one is distributed over it
+    fn distribute_recurse(self, outer: token::Kind) -> Res<Formula> {
+        let span = self.span();
         Ok(match self {
             Formula::Unary(_) => self, // should be only before a leaf
             Formula::Binary(x) => {
                 let (left, operator, right) = x.destroy();
-                let left = left.distribute_recurse()?;
-                let right = right.distribute_recurse()?;
+                let left = left.distribute_recurse(outer)?;
+                let right = right.distribute_recurse(outer)?;
                 match operator {
-                    token::Kind::And => Formula::new_binary(left, token::Kind::And, right),
-                    token::Kind::Or => {
-                        if left.is_and() {
-                            Formula::distribute_left(left, right)?
-                        } else if right.is_and() {
-                            Formula::distribute_right(left, right)?
+                    _ if operator == outer => Formula::new_binary(left, outer, right),
+                    token::Kind::And | token::Kind::Or => {
+                        if left.is_binary(outer) {
+                            Formula::distribute_left(left, right, span)?
+                        } else if right.is_binary(outer) {
+                            Formula::distribute_right(left, right, span)?
                         } else {
-                            Formula::new_binary(left, token::Kind::Or, right)
+                            Formula::new_binary(left, operator, right)
                         }
                     }
                     token::Kind::Implies | token::Kind::Equiv => {
@@ -231,58 +389,71 @@ impl Formula {
                     }
                     _ => panic!("not a valid binary operator"),
                 }
+                .with_span(span)
             }
             Formula::Leaf(_) => self,
         })
     }
 
-    /// assert left.is_and()
-    // distribute recursively
+    /// assert left is a binary node, its operator is the outer one
+    /// every inner node created has the `span` of the one distributed
+    // distribute recursively (here for the CNF, the DNF swaps `&` and `|`)
     //     |              &
     //   &   c         |     |
     //  a b           a c   b c
-    fn distribute_left(left: Formula, right: Formula) -> Res<Formula> {
+    fn distribute_left(left: Formula, right: Formula, span: Span) -> Res<Formula> {
         Ok(if let Formula::Binary(l) = left {
-            if l.operator == token::Kind::And {
-                let (a, _, b) = l.destroy();
-                let c = right;
-                Formula::new_binary(
-                    // I need 2 istances of c
-                    Formula::new_binary(a, token::Kind::Or, c.clone()).distribute_recurse()?,
-                    token::Kind::And,
-                    Formula::new_binary(b, token::Kind::Or, c).distribute_recurse()?,
-                )
-            } else {
-                panic!("assert left.is_and()");
-            }
+            let (a, outer, b) = l.destroy();
+            let inner = Formula::inner(outer);
+            let c = right;
+            Formula::new_binary(
+                // I need 2 istances of c
+                Formula::new_binary(a, inner, c.clone())
+                    .with_span(span)
+                    .distribute_recurse(outer)?,
+                outer,
+                Formula::new_binary(b, inner, c)
+                    .with_span(span)
+                    .distribute_recurse(outer)?,
+            )
         } else {
-            panic!("assert left.is_and()");
+            panic!("assert left is a binary node");
         })
     }
 
-    /// assert right.is_and()
-    /// distribute recursively
+    /// assert right is a binary node, its operator is the outer one
+    /// every inner node created has the `span` of the one distributed
+    /// distribute recursively (here for the CNF, the DNF swaps `&` and `|`)
     ///     |              &
     ///  c     &        |      |
     ///       a b      c a    c b
-    fn distribute_right(left: Formula, right: Formula) -> Res<Formula> {
+    fn distribute_right(left: Formula, right: Formula, span: Span) -> Res<Formula> {
         Ok(if let Formula::Binary(r) = right {
-            if r.operator == token::Kind::And {
-                let (a, _, b) = r.destroy();
-                let c = left;
-                Formula::new_binary(
-                    // I need 2 istances of c
-                    Formula::new_binary(c.clone(), token::Kind::Or, a).distribute_recurse()?,
-                    token::Kind::And,
-                    Formula::new_binary(c, token::Kind::Or, b).distribute_recurse()?,
-                )
-            } else {
-                panic!("assert right.is_and()");
-            }
+            let (a, outer, b) = r.destroy();
+            let inner = Formula::inner(outer);
+            let c = left;
+            Formula::new_binary(
+                // I need 2 istances of c
+                Formula::new_binary(c.clone(), inner, a)
+                    .with_span(span)
+                    .distribute_recurse(outer)?,
+                outer,
+                Formula::new_binary(c, inner, b)
+                    .with_span(span)
+                    .distribute_recurse(outer)?,
+            )
         } else {
-            panic!("assert right.is_and()");
+            panic!("assert right is a binary node");
         })
     }
+
+    /// the operator distributed over `outer`
+    fn inner(outer: token::Kind) -> token::Kind {
+        match outer {
+            token::Kind::And => token::Kind::Or,
+            _ => token::Kind::And,
+        }
+    }
 }
 
 impl fmt::Display for Formula {
@@ -1,6 +1,12 @@
-use crate::{context, parser::Parser};
+use crate::{
+    context,
+    model::Model,
+    parser::{Parser, Statement},
+    symbol::Symbol,
+    token::{self, Span},
+};
 
-use super::Statement;
+use super::{Formula, NormalForm, Simplified};
 
 #[test]
 fn test_digest() {
@@ -30,8 +36,8 @@ x | y => z;
         "END OF INPUT",
     ];
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
     let mut context = context::Context::new();
 
     for &exp in expected {
@@ -63,8 +69,8 @@ a & (b | c | (d & e & (f | g)))
         "(a & ((((b | c) | d) & ((b | c) | e)) & ((b | c) | (f | g))))",
     ];
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
     let mut context = context::Context::new();
 
     for &exp in expected {
@@ -82,3 +88,191 @@ a & (b | c | (d & e & (f | g)))
         }
     }
 }
+
+#[test]
+fn test_span() {
+    let buffer = "
+~a & (b | c) => d
+0
+";
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let mut context = context::Context::new();
+
+    let f = match pars.parse_statement_update_context(&mut context) {
+        Ok(Statement::Formula(f)) => f,
+        _ => panic!("expected a formula"),
+    };
+    assert_eq!(f.span(), Span::on_row(2, 1, 17));
+    let Formula::Binary(implies) = &f else {
+        panic!("expected `=>`")
+    };
+    let (and, _, d) = implies.parts();
+    assert_eq!(and.span(), Span::on_row(2, 1, 12));
+    assert_eq!(d.span(), Span::on_row(2, 17, 1));
+    let Formula::Binary(and) = and else {
+        panic!("expected `&`")
+    };
+    let (not, _, paren) = and.parts();
+    assert_eq!(not.span(), Span::on_row(2, 1, 2));
+    assert_eq!(paren.span(), Span::on_row(2, 6, 7));
+
+    // the derived nodes keep the span of the originating ones
+    let dist = f.clone().distribute().unwrap();
+    assert_eq!(dist.span(), f.span());
+    let Formula::Binary(x) = &dist else {
+        panic!("expected `&`")
+    };
+    let (or, _, _) = x.parts();
+    assert_eq!(or.span(), f.span());
+
+    match pars.parse_statement_update_context(&mut context) {
+        Ok(Statement::Formula(f)) => assert_eq!(f.span(), Span::on_row(3, 1, 1)),
+        _ => panic!("expected a formula"),
+    };
+}
+
+#[test]
+fn test_eval() {
+    let mut pars = Parser::new();
+    pars.load_bytes("(a => b) <=> ~c | a".to_string());
+    let Statement::Formula(f) = pars.parse_statement(&context::Context::new()).unwrap() else {
+        panic!("not a formula")
+    };
+    let symbols = f.symbols();
+    assert_eq!(symbols, ["a", "b", "c"].map(Symbol::intern));
+    let mut model = Model::new();
+    model.set(symbols[0], true);
+    model.set(symbols[1], false);
+    assert_eq!(f.eval(&model), None);
+    model.set(symbols[2], true);
+    assert_eq!(f.eval(&model), Some(false));
+    model.set(symbols[1], true);
+    assert_eq!(f.eval(&model), Some(true));
+}
+
+/// true if the only binary operators are in `operators`, and `~` is only before
+/// an atom
+fn has_only(f: &Formula, operators: &[token::Kind]) -> bool {
+    match f {
+        Formula::Leaf(_) => true,
+        Formula::Unary(x) => matches!(x.right(), Formula::Leaf(_)),
+        Formula::Binary(x) => {
+            let (l, operator, r) = x.parts();
+            operators.contains(&operator) && has_only(l, operators) && has_only(r, operators)
+        }
+    }
+}
+
+/// true if `f` is a chain of `outer` of chains of the other operator
+fn is_normal(f: &Formula, outer: token::Kind) -> bool {
+    match f {
+        Formula::Binary(x) if x.parts().1 == outer => {
+            is_normal(x.parts().0, outer) && is_normal(x.parts().2, outer)
+        }
+        _ if outer == token::Kind::And => has_only(f, &[token::Kind::Or]),
+        _ => has_only(f, &[token::Kind::And]),
+    }
+}
+
+#[test]
+fn test_normal_form() {
+    let buffer = "
+(a | b) & (c | d)
+a <=> b
+~(a => (b <=> ~c)) | d
+~~(a => b)
+~(a <=> (b => c))
+~~~(a <=> b)
+";
+    let expected: &[[&str; 3]] = &[
+        [
+            "((a | b) & (c | d))",
+            "((a | b) & (c | d))",
+            "(((a & c) | (a & d)) | ((b & c) | (b & d)))",
+        ],
+        [
+            "((a & b) | ((~a) & (~b)))",
+            "((a | (~b)) & (b | (~a)))",
+            "((a & b) | ((~a) & (~b)))",
+        ],
+        [
+            "((a & ((b & c) | ((~b) & (~c)))) | d)",
+            "((a | d) & (((b | (~c)) | d) & ((c | (~b)) | d)))",
+            "(((a & (b & c)) | (a & ((~b) & (~c)))) | d)",
+        ],
+        ["((~a) | b)", "((~a) | b)", "((~a) | b)"],
+        [
+            "((a & (b & (~c))) | ((~a) & ((~b) | c)))",
+            "((a | ((~b) | c)) & ((b | (~a)) & ((~c) | (~a))))",
+            "((a & (b & (~c))) | (((~a) & (~b)) | ((~a) & c)))",
+        ],
+        [
+            "((a & (~b)) | ((~a) & b))",
+            "((a | b) & ((~b) | (~a)))",
+            "((a & (~b)) | ((~a) & b))",
+        ],
+    ];
+
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = context::Context::new();
+    let forms = [
+        NormalForm::Negation,
+        NormalForm::Conjunctive,
+        NormalForm::Disjunctive,
+    ];
+    for exp in expected {
+        let Statement::Formula(f) = pars.parse_statement(&context).unwrap() else {
+            panic!("not a formula")
+        };
+        let symbols = f.symbols();
+        for (form, exp) in forms.into_iter().zip(exp) {
+            let Simplified::Formula(normal) = f.clone().normal_form(form).unwrap() else {
+                panic!("not a formula")
+            };
+            assert_eq!(normal.to_string(), *exp);
+            let normal_shape = match form {
+                NormalForm::Negation => has_only(&normal, &[token::Kind::And, token::Kind::Or]),
+                NormalForm::Conjunctive => is_normal(&normal, token::Kind::And),
+                NormalForm::Disjunctive => is_normal(&normal, token::Kind::Or),
+            };
+            assert!(normal_shape, "{form} {normal}");
+            // equivalent in every model
+            for n in 0..1 << symbols.len() {
+                let mut model = Model::new();
+                for (i, &s) in symbols.iter().enumerate() {
+                    model.set(s, n >> i & 1 == 1);
+                }
+                assert_eq!(normal.eval(&model), f.eval(&model), "{form} {f}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_normal_form_complementary() {
+    let buffer = "
+(a | ~a) & b
+a | ~a
+a & ~a
+";
+    // the CNF and the DNF of each formula
+    let expected: &[[&str; 2]] = &[
+        ["b", "((a & b) | ((~a) & b))"],
+        ["true", "(a | (~a))"],
+        ["(a & (~a))", "false"],
+    ];
+
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = context::Context::new();
+    for exp in expected {
+        let Statement::Formula(f) = pars.parse_statement(&context).unwrap() else {
+            panic!("not a formula")
+        };
+        let cnf = f.clone().normal_form(NormalForm::Conjunctive).unwrap();
+        let dnf = f.normal_form(NormalForm::Disjunctive).unwrap();
+        assert_eq!([cnf.to_string(), dnf.to_string()], *exp);
+    }
+}
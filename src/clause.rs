@@ -1,119 +1,246 @@
 use crate::ast;
 use crate::context::Context;
+use crate::fragment::Fragment;
+use crate::linear;
+use crate::model::Model;
+use crate::order::{Order, Ranking};
+use crate::symbol::Symbol;
 use crate::token;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::rc::{Rc, Weak};
+use std::hash::{Hash, Hasher};
 
 #[cfg(test)]
 mod test;
 
-#[derive(Hash, Ord, PartialOrd, Clone, Debug)]
-/// Equal is implementented using ptr
-pub enum Atom {
-    Positive(Rc<str>),
-    Negative(Rc<str>),
+/// the id of the symbol times 2, plus 1 if it is negated:
+/// a literal and its opposite differ only in the last bit
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+pub struct Lit(u32);
+
+impl Lit {
+    pub fn positive(s: Symbol) -> Lit {
+        Lit(s.id() << 1)
+    }
+    pub fn negative(s: Symbol) -> Lit {
+        Lit(s.id() << 1 | 1)
+    }
+    pub fn symbol(&self) -> Symbol {
+        Symbol::from_id(self.0 >> 1)
+    }
+    pub fn is_negative(&self) -> bool {
+        self.0 & 1 == 1
+    }
+    pub fn opposite(&self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+    /// the integer representation
+    pub fn code(&self) -> u32 {
+        self.0
+    }
+    fn var(&self) -> u32 {
+        self.0 >> 1
+    }
 }
 
-impl PartialEq for Atom {
-    fn eq(&self, other: &Self) -> bool {
-        if let (Atom::Positive(a), Atom::Positive(b)) = (self, other) {
-            a.as_ptr() == b.as_ptr()
-        } else if let (Atom::Negative(a), Atom::Negative(b)) = (self, other) {
-            a.as_ptr() == b.as_ptr()
+impl fmt::Display for Lit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "{}{}", token::Kind::Not, self.symbol())
         } else {
-            false
+            write!(f, "{}", self.symbol())
         }
     }
 }
-impl Eq for Atom {}
 
-impl Atom {
-    pub fn new_affermative(s: Rc<str>) -> Atom {
-        Atom::Positive(s)
-    }
-    pub fn new_negative(s: Rc<str>) -> Atom {
-        Atom::Negative(s)
-    }
-    pub fn opposite(&self) -> Atom {
-        match self {
-            Atom::Positive(x) => Atom::Negative(Rc::clone(x)),
-            Atom::Negative(x) => Atom::Positive(Rc::clone(x)),
-        }
+/// prints the literals of a clause:
+/// sort: positive before negative, then lexological order
+pub struct Lits<'a>(pub &'a [Lit]);
+
+impl fmt::Display for Lits<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut lits = self.0.to_vec();
+        lits.sort_by_key(|x| (x.is_negative(), x.symbol()));
+        let s = lits
+            .iter()
+            .map(|x| x.to_string())
+            .reduce(|acc, s| format!("{acc}, {s}"))
+            .unwrap_or_default();
+        write!(f, "{{{s}}}",)
     }
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Debug)]
+/// literals sorted by code, without duplicates
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
 pub struct Clause {
-    c: BTreeSet<Atom>,
+    lits: Vec<Lit>,
 }
 
 impl Clause {
-    fn new() -> Clause {
-        Clause { c: BTreeSet::new() }
+    pub fn new() -> Clause {
+        Clause { lits: Vec::new() }
+    }
+    pub fn lits(&self) -> &[Lit] {
+        &self.lits
+    }
+    pub fn len(&self) -> usize {
+        self.lits.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lits.is_empty()
+    }
+    /// returns false if the opposite literal is already there:
+    /// it is useless to have a clause like {~x, x, ...}
+    pub fn insert(&mut self, lit: Lit) -> bool {
+        match self.lits.binary_search(&lit.opposite()) {
+            Ok(_) => false,
+            Err(_) => {
+                if let Err(i) = self.lits.binary_search(&lit) {
+                    self.lits.insert(i, lit);
+                }
+                true
+            }
+        }
     }
 }
 
-impl From<BTreeSet<Atom>> for Clause {
-    fn from(c: BTreeSet<Atom>) -> Clause {
-        Clause { c }
+impl From<Vec<Lit>> for Clause {
+    fn from(mut lits: Vec<Lit>) -> Clause {
+        lits.sort();
+        lits.dedup();
+        Clause { lits }
     }
 }
 
 impl fmt::Display for Clause {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let s = self
-            .c
-            .iter()
-            .map(|x| match x {
-                Atom::Positive(x) => format!("{x}"),
-                Atom::Negative(x) => format!("{}{x}", token::Kind::Not),
-            })
-            .reduce(|acc, s| format!("{acc}, {s}"))
-            .unwrap_or_default();
-        write!(f, "{{{s}}}",)
+        write!(f, "{}", Lits(&self.lits))
     }
 }
 
-/// sort: positive before negative, then lexological order
+/// index of a clause in its `SetClauses`
+pub type ClauseId = u32;
+
+/// the two parents a clause has been resolved from (None if it is an input clause)
+pub type Parents = Option<(ClauseId, ClauseId)>;
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    start: u32,
+    len: u32,
+    parents: Parents,
+    /// the premises after the parents (hyper-resolution), in `SetClauses::premises`
+    more_start: u32,
+    more_len: u32,
+    /// kept only for the proofs, it is not resolved anymore
+    eliminated: bool,
+}
+
+/// which pairs of clauses are resolved by `SetClauses::saturate()`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub enum Restriction {
+    /// every pair that has a resolvent
+    #[default]
+    None,
+    /// only upon the literals eligible in both, see `Ranking::eligible()`
+    Ordered(Order),
+    /// one of the two clauses has only positive literals
+    Positive,
+    /// one of the two clauses has only negative literals
+    Negative,
+    /// a clause with negative literals (the nucleus) is resolved at once with
+    /// a clause with only positive literals (an electron) for each negative
+    /// literal: the resolvent has only positive literals
+    Hyper,
+    /// each resolvent is resolved with an input clause or with one of the
+    /// clauses it was derived from: the proof is a chain, see `linear`
+    Linear,
+    /// one of the two clauses is an input clause: the proof is a chain, but
+    /// the box is always found only for Horn clauses
+    Input,
+}
+
+impl Restriction {
+    /// true if `c1` and `c2` can be resolved (`Hyper` and `Linear` do not
+    /// resolve pairs)
+    fn allows(
+        &self,
+        set: &SetClauses,
+        ranking: Option<&Ranking>,
+        c1: ClauseId,
+        c2: ClauseId,
+    ) -> bool {
+        let (l1, l2) = (set.clause(c1), set.clause(c2));
+        let positive = |c: &[Lit]| c.iter().all(|x| !x.is_negative());
+        let negative = |c: &[Lit]| c.iter().all(|x| x.is_negative());
+        match self {
+            Restriction::None => true,
+            Restriction::Ordered(_) => ranking.is_some_and(|r| r.allows(l1, l2)),
+            Restriction::Positive => positive(l1) || positive(l2),
+            Restriction::Negative => negative(l1) || negative(l2),
+            Restriction::Hyper | Restriction::Linear => false,
+            Restriction::Input => set.parents(c1).is_none() || set.parents(c2).is_none(),
+        }
+    }
+
+    /// false if the box could be missed for the clauses not eliminated of `set`
+    pub fn is_complete_for(&self, set: &SetClauses) -> bool {
+        match self {
+            Restriction::Input => Fragment::of(set) == Fragment::Horn,
+            _ => true,
+        }
+    }
+}
+
+/// a clause found by resolving the clauses at positions `pair` of a round
+#[derive(Debug)]
+struct Resolvent {
+    pair: (usize, usize),
+    clause: Clause,
+    parents: (ClauseId, ClauseId),
+}
+
+/// an arena of clauses: the literals of all the clauses are stored one after
+/// the other, and each clause is identified by its position
 #[derive(Default, Clone, Debug)]
 pub struct SetClauses {
-    // using a BTreeSet i should avoid duplicates
-    bt: BTreeMap<Rc<Clause>, Option<(Weak<Clause>, Weak<Clause>)>>,
+    lits: Vec<Lit>,
+    clauses: Vec<Entry>,
+    // using the hash of the literals i should avoid duplicates
+    dedup: HashMap<u64, Vec<ClauseId>>,
+    /// the clauses each literal appears in
+    occurs: HashMap<Lit, Vec<ClauseId>>,
+    /// the premises of the clauses found with more than two
+    premises: Vec<ClauseId>,
+    /// the clauses before this one have already been resolved with each other
+    resolved: ClauseId,
 }
 
 impl From<&Context> for SetClauses {
     fn from(c: &Context) -> SetClauses {
-        let bt = c
-            .inner()
+        c.inner()
             .iter()
             .map(|x| x.set_clauses())
-            .fold(BTreeMap::new(), |mut acc, x| {
-                for x in x.as_ref().bt.iter() {
-                    acc.insert(Rc::clone(x.0), x.1.clone()); // it uses Weak::clone() inside
-                }
+            .fold(SetClauses::default(), |mut acc, x| {
+                acc.extend(&x);
                 acc
-            });
-        SetClauses { bt }
+            })
     }
 }
 
 impl From<Vec<SetClauses>> for SetClauses {
     fn from(clauses: Vec<SetClauses>) -> SetClauses {
-        SetClauses {
-            bt: clauses.into_iter().fold(BTreeMap::new(), |mut acc, x| {
-                acc.extend(x.bt);
-                acc
-            }),
-        }
+        clauses.iter().fold(SetClauses::default(), |mut acc, x| {
+            acc.extend(x);
+            acc
+        })
     }
 }
 
 impl From<&ast::Formula> for SetClauses {
     fn from(formula: &ast::Formula) -> SetClauses {
-        let mut c = SetClauses {
-            bt: BTreeMap::new(),
-        };
+        let mut c = SetClauses::default();
         c.append_formula(formula);
         c
     }
@@ -122,28 +249,241 @@ impl From<&ast::Formula> for SetClauses {
 impl fmt::Display for SetClauses {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let s = self
-            .bt
-            .iter()
-            .map(|x| x.0.to_string())
+            .print_order()
+            .into_iter()
+            .map(|id| Lits(self.clause(id)).to_string())
             .reduce(|acc, s| format!("{acc}, {s}"))
             .unwrap_or_default();
         write!(f, "{{{s}}}",)
     }
 }
 
+/// the resolvent of two clauses, None if they cannot be resolved or the
+/// resolvent would be a tautology
+pub(crate) fn resolve(l1: &[Lit], l2: &[Lit]) -> Option<Clause> {
+    // both are sorted: a literal and its opposite are next to each other
+    let mut lits = Vec::with_capacity(l1.len() + l2.len());
+    let mut pivot = None;
+    let (mut i, mut j) = (0, 0);
+    while i < l1.len() && j < l2.len() {
+        let (a, b) = (l1[i], l2[j]);
+        if a.var() < b.var() {
+            lits.push(a);
+            i += 1;
+        } else if a.var() > b.var() {
+            lits.push(b);
+            j += 1;
+        } else {
+            if a == b {
+                lits.push(a);
+            } else if pivot.is_some() {
+                // pruning: it is useless to have a clause like {!x, x, ...}
+                return None;
+            } else {
+                pivot = Some(a);
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+    pivot?;
+    lits.extend_from_slice(&l1[i..]);
+    lits.extend_from_slice(&l2[j..]);
+    Some(Clause { lits })
+}
+
+fn hash_lits(lits: &[Lit]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    lits.hash(&mut hasher);
+    hasher.finish()
+}
+
 impl SetClauses {
+    pub fn len(&self) -> usize {
+        self.clauses.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+    pub fn ids(&self) -> impl Iterator<Item = ClauseId> {
+        0..self.clauses.len() as ClauseId
+    }
+    pub fn clause(&self, id: ClauseId) -> &[Lit] {
+        let e = self.clauses[id as usize];
+        &self.lits[e.start as usize..(e.start + e.len) as usize]
+    }
+    pub fn parents(&self, id: ClauseId) -> Parents {
+        self.clauses[id as usize].parents
+    }
+    /// all the clauses `id` has been found from: the parents, and for
+    /// hyper-resolution the other electrons
+    pub fn premises(&self, id: ClauseId) -> Vec<ClauseId> {
+        let e = self.clauses[id as usize];
+        let more = &self.premises[e.more_start as usize..(e.more_start + e.more_len) as usize];
+        e.parents
+            .into_iter()
+            .flat_map(|(a, b)| [a, b])
+            .chain(more.iter().copied())
+            .collect()
+    }
+    /// the premises after the parents
+    fn more_premises(&self, id: ClauseId) -> &[ClauseId] {
+        let e = self.clauses[id as usize];
+        &self.premises[e.more_start as usize..(e.more_start + e.more_len) as usize]
+    }
+    /// eliminated clauses are still in the set (they can be part of a proof),
+    /// but they are not resolved anymore
+    pub fn is_eliminated(&self, id: ClauseId) -> bool {
+        self.clauses[id as usize].eliminated
+    }
+    pub(crate) fn eliminate(&mut self, id: ClauseId) {
+        self.clauses[id as usize].eliminated = true;
+    }
+    /// brings back all the eliminated clauses, returns false if there were none
+    pub(crate) fn restore(&mut self) -> bool {
+        let mut restored = false;
+        for e in &mut self.clauses {
+            restored |= e.eliminated;
+            e.eliminated = false;
+        }
+        if restored {
+            // they have not been resolved with the clauses found in the meantime
+            self.resolved = 0;
+        }
+        restored
+    }
+    /// the ids of the clauses not eliminated
+    pub fn active(&self) -> impl Iterator<Item = ClauseId> + '_ {
+        self.ids().filter(|&id| !self.is_eliminated(id))
+    }
+    /// the symbols of the clauses not eliminated, with repetitions
+    pub fn active_symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.active()
+            .flat_map(|id| self.clause(id).iter().map(|x| x.symbol()))
+    }
+    /// `lits` must be sorted without duplicates, see `Clause`
+    pub fn find(&self, lits: &[Lit]) -> Option<ClauseId> {
+        self.find_hashed(lits, hash_lits(lits))
+    }
+    fn find_hashed(&self, lits: &[Lit], hash: u64) -> Option<ClauseId> {
+        self.dedup
+            .get(&hash)?
+            .iter()
+            .copied()
+            .find(|&id| self.clause(id) == lits)
+    }
+    pub fn contains(&self, lits: &[Lit]) -> bool {
+        self.find(lits).is_some()
+    }
+    /// the ids of the clauses containing `lit`, in increasing order
+    pub fn occurrences(&self, lit: Lit) -> &[ClauseId] {
+        self.occurs.get(&lit).map_or(&[], |v| v.as_slice())
+    }
+
+    /// adds an input clause, returns its id
+    pub fn insert(&mut self, clause: &Clause) -> ClauseId {
+        self.push(clause.lits(), None)
+    }
+
+    /// the resolvent of `c1` and `c2`, None if they cannot be resolved or the
+    /// resolvent would be a tautology
+    pub(crate) fn resolvent(&self, c1: ClauseId, c2: ClauseId) -> Option<Clause> {
+        self.extend_solve(c1, c2).map(|(c, _, _)| c)
+    }
+    /// adds the resolvent of `c1` and `c2`, returns its id; None if they cannot be
+    /// resolved or the resolvent would be a tautology
+    pub(crate) fn insert_resolvent(&mut self, c1: ClauseId, c2: ClauseId) -> Option<ClauseId> {
+        let (c, c1, c2) = self.extend_solve(c1, c2)?;
+        Some(self.push(c.lits(), Some((c1, c2))))
+    }
+
+    /// returns the id of the clause, which is not added again if already there:
+    /// its parents are the first ones it was found with
+    fn push(&mut self, lits: &[Lit], parents: Parents) -> ClauseId {
+        self.push_premises(lits, parents, &[])
+    }
+    /// like `push()`, with the premises that come after the parents
+    fn push_premises(&mut self, lits: &[Lit], parents: Parents, more: &[ClauseId]) -> ClauseId {
+        let hash = hash_lits(lits);
+        if let Some(id) = self.find_hashed(lits, hash) {
+            return id;
+        }
+        let id = self.clauses.len() as ClauseId;
+        self.clauses.push(Entry {
+            start: self.lits.len() as u32,
+            len: lits.len() as u32,
+            parents,
+            more_start: self.premises.len() as u32,
+            more_len: more.len() as u32,
+            eliminated: false,
+        });
+        self.premises.extend_from_slice(more);
+        self.lits.extend_from_slice(lits);
+        self.dedup.entry(hash).or_default().push(id);
+        for &lit in lits {
+            self.occurs.entry(lit).or_default().push(id);
+        }
+        id
+    }
+
+    /// adds all the clauses of `other` (with their parents)
+    pub fn extend(&mut self, other: &SetClauses) {
+        let mut ids = Vec::with_capacity(other.len());
+        for id in other.ids() {
+            // parents always come before their children
+            let parents = other
+                .parents(id)
+                .map(|(a, b)| (ids[a as usize], ids[b as usize]));
+            let more: Vec<ClauseId> = other
+                .more_premises(id)
+                .iter()
+                .map(|&c| ids[c as usize])
+                .collect();
+            ids.push(self.push_premises(other.clause(id), parents, &more));
+        }
+    }
+
+    /// the ids sorted as the clauses are printed: the proof found does not
+    /// depend on the order in which the symbols have been interned
+    fn print_order(&self) -> Vec<ClauseId> {
+        let vars: HashSet<u32> = self.lits.iter().map(|x| x.var()).collect();
+        let mut vars: Vec<u32> = vars.into_iter().collect();
+        vars.sort_by_key(|&v| Symbol::from_id(v));
+        let rank: HashMap<u32, u32> = vars
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (v, i as u32))
+            .collect();
+        let positive_last = rank.len() as u32;
+        let keys: Vec<Vec<u32>> = self
+            .ids()
+            .map(|id| {
+                let mut key: Vec<u32> = self
+                    .clause(id)
+                    .iter()
+                    .map(|x| rank[&x.var()] + if x.is_negative() { positive_last } else { 0 })
+                    .collect();
+                key.sort_unstable();
+                key
+            })
+            .collect();
+        let mut ids: Vec<ClauseId> = self.ids().collect();
+        ids.sort_by(|&a, &b| keys[a as usize].cmp(&keys[b as usize]));
+        ids
+    }
+
     fn append_formula(&mut self, formula: &ast::Formula) {
         // find `or` recursively than call append_to_clause()
         match formula {
             ast::Formula::Binary(x) => {
                 let (left, operator, right) = x.parts();
                 if operator == token::Kind::Or {
-                    let mut bt = Clause::new();
+                    let mut c = Clause::new();
                     // compiler does not evaluate the second expression if the first one is false
-                    if SetClauses::append_atom(&mut bt, left)
-                        && SetClauses::append_atom(&mut bt, right)
+                    if SetClauses::append_atom(&mut c, left)
+                        && SetClauses::append_atom(&mut c, right)
                     {
-                        self.bt.insert(Rc::new(bt), None);
+                        self.insert(&c);
                     }
                 } else {
                     debug_assert!(operator == token::Kind::And);
@@ -152,178 +492,496 @@ impl SetClauses {
                 }
             }
             ast::Formula::Unary(_) | ast::Formula::Leaf(_) => {
-                let mut bt = Clause::new();
-                if SetClauses::append_atom(&mut bt, formula) {
-                    self.bt.insert(Rc::new(bt), None);
+                let mut c = Clause::new();
+                if SetClauses::append_atom(&mut c, formula) {
+                    self.insert(&c);
                 }
             }
         };
     }
 
-    fn append_atom(bt: &mut Clause, f: &ast::Formula) -> bool {
+    fn append_atom(c: &mut Clause, f: &ast::Formula) -> bool {
         match f {
             ast::Formula::Binary(x) => {
                 let (left, operator, right) = x.parts();
                 debug_assert!(operator == token::Kind::Or);
                 // compiler does not evaluate the second expression if the first one is false
-                SetClauses::append_atom(bt, left) && SetClauses::append_atom(bt, right)
+                SetClauses::append_atom(c, left) && SetClauses::append_atom(c, right)
             }
             ast::Formula::Unary(x) => {
                 debug_assert!(x.operator() == token::Kind::Not);
                 if let ast::Formula::Leaf(x) = x.right() {
-                    // pruning: it is useless to have a clause like {!x, x, ...}
-                    if bt.c.contains(&Atom::Positive(x.string())) {
-                        // comparing 2 atoms is not slow because it uses
-                        // pointers of the inner Rc value
-                        false
-                    } else {
-                        bt.c.insert(Atom::Negative(x.string()));
-                        true
-                    }
+                    c.insert(Lit::negative(x.symbol()))
                 } else {
                     panic!("this should be a leaf, see ast::Formula::digest()");
                 }
             }
-            ast::Formula::Leaf(x) => {
-                // pruning: it is useless to have a clause like {!x, x, ...}
-                if bt.c.contains(&Atom::Negative(x.string())) {
-                    false
-                } else {
-                    bt.c.insert(Atom::Positive(x.string()));
-                    true
-                }
-            }
+            ast::Formula::Leaf(x) => c.insert(Lit::positive(x.symbol())),
         }
     }
 
-    fn prune(&mut self) {
-        let mut hm = HashMap::new();
-        for (v, _) in &self.bt {
-            for w in &v.as_ref().c {
-                match w {
-                    Atom::Positive(x) => hm
-                        .entry(Rc::clone(x))
-                        .and_modify(|(b, _): &mut (bool, bool)| *b = true)
-                        .or_insert((true, false)),
-                    Atom::Negative(x) => hm
-                        .entry(Rc::clone(x))
-                        .and_modify(|(_, b): &mut (bool, bool)| *b = true)
-                        .or_insert((false, true)),
+    /// keeps only the clauses for which `f` is true (their parents must be kept too)
+    fn retain(&mut self, f: impl Fn(&[Lit]) -> bool) {
+        self.retain_ids(|set, id| f(set.clause(id)));
+    }
+
+    /// the clauses before `resolved()` have already been resolved with each other
+    pub(crate) fn resolved(&self) -> ClauseId {
+        self.resolved
+    }
+    /// `resolved` must not be more than `resolved()` was when the clauses before
+    /// it were last changed
+    pub(crate) fn set_resolved(&mut self, resolved: ClauseId) {
+        self.resolved = resolved.min(self.len() as ClauseId);
+    }
+
+    /// like `retain()`, but `f` gets the id of the clause: the clauses kept
+    /// before the first one removed keep their id. Nothing is considered resolved anymore
+    /// returns the new id of each clause
+    pub(crate) fn retain_ids(
+        &mut self,
+        f: impl Fn(&SetClauses, ClauseId) -> bool,
+    ) -> Vec<Option<ClauseId>> {
+        let old = std::mem::take(self);
+        let mut ids = vec![None; old.len()];
+        for id in old.ids() {
+            if f(&old, id) {
+                let new_id = |c: ClauseId| -> ClauseId {
+                    ids[c as usize].expect("parents are removed with their children")
                 };
+                let parents = old.parents(id).map(|(a, b)| (new_id(a), new_id(b)));
+                let more: Vec<ClauseId> =
+                    old.more_premises(id).iter().map(|&c| new_id(c)).collect();
+                let new = self.push_premises(old.clause(id), parents, &more);
+                self.clauses[new as usize].eliminated = old.is_eliminated(id);
+                ids[id as usize] = Some(new);
             }
         }
-        self.bt.retain(|v, _| {
-            for w in &v.as_ref().c {
-                let b = match w {
-                    Atom::Positive(x) => hm[x].1,
-                    Atom::Negative(x) => hm[x].0,
-                };
-                if b == false {
-                    return false;
-                }
-            }
-            true
-        });
+        ids
+    }
+
+    /// removes the clauses with a pure literal (whose opposite is never used)
+    fn prune(&mut self) {
+        let used: HashSet<Lit> = self.lits.iter().copied().collect();
+        self.retain(|c| c.iter().all(|x| used.contains(&x.opposite())));
     }
 
-    // @todo? Horn... Nah, I don't think i will
+    /// resolves the clauses until the box is found or nothing new can be found,
+    /// whatever the fragment: `Solver` uses the faster algorithms of `Fragment`
     pub fn find_box(&mut self) -> bool {
+        self.find_box_jobs(1)
+    }
+
+    /// like `find_box()`, resolving only the pairs allowed by `restriction`:
+    /// it could miss the box, see `Restriction::is_complete_for()`
+    pub fn find_box_with(&mut self, restriction: &Restriction) -> bool {
+        self.prune();
+        self.saturate(1, false, restriction)
+    }
+
+    /// like `find_box()`, but each round is split among `jobs` threads: the
+    /// result (and the proof) is the same for any number of threads
+    pub fn find_box_jobs(&mut self, jobs: usize) -> bool {
         self.prune();
-        let mut previous_len = 0;
-        while previous_len != self.bt.len() {
-            previous_len = self.bt.len();
-            if self.square() {
+        self.saturate(jobs, false, &Restriction::None)
+    }
+
+    /// resolves the clauses until the box is found or nothing new can be found;
+    /// the pairs already resolved by a previous call are not tried again.
+    /// With `propagate` the unit clauses are propagated before each round
+    pub(crate) fn saturate(
+        &mut self,
+        jobs: usize,
+        propagate: bool,
+        restriction: &Restriction,
+    ) -> bool {
+        if self.contains(&[]) {
+            return true;
+        }
+        if *restriction == Restriction::Linear {
+            return linear::find_chain(self);
+        }
+        loop {
+            // a unit clause could be resolved with clauses derived from it
+            if propagate && *restriction != Restriction::Input && self.propagate() {
+                return true;
+            }
+            let previous_len = self.len();
+            if self.square(jobs.max(1), restriction) {
                 return true;
             }
+            self.resolved = previous_len as ClauseId;
+            if previous_len == self.len() {
+                return false;
+            }
+        }
+    }
+
+    /// unit propagation: each unit clause `{l}` is resolved with the clauses
+    /// with `~l`, which are then eliminated as the resolvent is a subset of them;
+    /// the clauses with `l` are eliminated too. Returns true if the box is found
+    pub(crate) fn propagate(&mut self) -> bool {
+        let mut units: Vec<ClauseId> = self
+            .active()
+            .filter(|&id| self.clause(id).len() == 1)
+            .collect();
+        let mut next = 0;
+        while next < units.len() {
+            let unit = units[next];
+            next += 1;
+            if self.is_eliminated(unit) {
+                continue;
+            }
+            let lit = self.clause(unit)[0];
+            for id in self.occurrences(lit).to_vec() {
+                if id != unit {
+                    self.eliminate(id);
+                }
+            }
+            for id in self.occurrences(lit.opposite()).to_vec() {
+                if self.is_eliminated(id) {
+                    continue;
+                }
+                let resolvent = self
+                    .insert_resolvent(unit, id)
+                    .expect("a unit clause always resolves with its opposite");
+                self.eliminate(id);
+                match self.clause(resolvent).len() {
+                    0 => return true,
+                    1 => units.push(resolvent),
+                    _ => {}
+                }
+            }
         }
         false
     }
 
     /// returns true if box if found
-    fn square(&mut self) -> bool {
-        let mut new_clauses = SetClauses::default();
+    fn square(&mut self, jobs: usize, restriction: &Restriction) -> bool {
+        let mut order = self.print_order();
+        order.retain(|&id| !self.is_eliminated(id));
+        let ranking = match restriction {
+            Restriction::Ordered(o) => Some(o.ranking(self.active_symbols())),
+            Restriction::Hyper => return self.hyper(&order),
+            _ => None,
+        };
+        let ranking = ranking.as_ref();
+        // the eliminated clauses are at 0, so they are never after another clause
+        let mut position = vec![0; self.len()];
+        for (i, &id) in order.iter().enumerate() {
+            position[id as usize] = i;
+        }
+        // the thread `t` takes the clauses at positions t, t + jobs, t + 2 * jobs, ...
+        let mut resolvents = if jobs == 1 {
+            self.resolvents(&order, &position, restriction, ranking, 0, 1)
+        } else {
+            let (this, order, position) = (&*self, &order, &position);
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..jobs)
+                    .map(|t| {
+                        scope.spawn(move || {
+                            this.resolvents(order, position, restriction, ranking, t, jobs)
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|h| h.join().expect("a resolution thread panicked"))
+                    .collect()
+            })
+        };
+        // the same order as if the pairs were visited by a single thread
+        resolvents.sort_unstable_by_key(|r| r.pair);
+
+        // the clauses found in this round are not used until the next one
+        let mut new_clauses: Vec<(Clause, (ClauseId, ClauseId))> = Vec::new();
+        let mut new_ids: HashMap<Clause, usize> = HashMap::new();
         let mut found = false;
-        for (i, c1) in self.bt.iter().enumerate() {
-            // is skip efficient? magic...
-            for c2 in self.bt.iter().skip(i) {
-                found |= new_clauses.extend_solve(Rc::clone(c1.0), Rc::clone(c2.0), self);
-                if found {
-                    break;
+        for r in resolvents {
+            found = r.clause.is_empty();
+            // a clause found twice keeps the last parents
+            match new_ids.get(&r.clause) {
+                Some(&i) => new_clauses[i].1 = r.parents,
+                None => {
+                    new_ids.insert(r.clause.clone(), new_clauses.len());
+                    new_clauses.push((r.clause, r.parents));
                 }
             }
+            if found {
+                break;
+            }
+        }
+        for (c, parents) in new_clauses {
+            self.push(c.lits(), Some(parents));
         }
-        self.bt.extend(new_clauses.bt);
         found
     }
 
-    /// returns true if box if found
-    fn extend_solve(&mut self, c1: Rc<Clause>, c2: Rc<Clause>, parent: &SetClauses) -> bool {
-        let (c1, c2) = if c1.c.len() < c2.c.len() {
+    /// the new resolvents of the clauses at positions `first`, `first + step`, ...
+    /// with the ones after them, up to the first empty clause
+    fn resolvents(
+        &self,
+        order: &[ClauseId],
+        position: &[usize],
+        restriction: &Restriction,
+        ranking: Option<&Ranking>,
+        first: usize,
+        step: usize,
+    ) -> Vec<Resolvent> {
+        let mut resolvents = Vec::new();
+        let mut marks = vec![0; order.len() / 64 + 1];
+        for i in (first..order.len()).step_by(step) {
+            for j in self.partners(order[i], i, position, &mut marks) {
+                if !restriction.allows(self, ranking, order[i], order[j]) {
+                    continue;
+                }
+                if let Some((c, c1, c2)) = self.extend_solve(order[i], order[j]) {
+                    if self.contains(c.lits()) {
+                        continue;
+                    }
+                    let found = c.is_empty();
+                    resolvents.push(Resolvent {
+                        pair: (i, j),
+                        clause: c,
+                        parents: (c1, c2),
+                    });
+                    if found {
+                        // the pairs after this one are not needed
+                        return resolvents;
+                    }
+                }
+            }
+        }
+        resolvents
+    }
+
+    /// a round of hyper-resolution, see `Restriction::Hyper`: the clauses of
+    /// `order` are the nuclei in turn, the electrons of each negative literal
+    /// are taken in order. Returns true if the box is found
+    fn hyper(&mut self, order: &[ClauseId]) -> bool {
+        let positive = |c: &[Lit]| c.iter().all(|x| !x.is_negative());
+        let electrons: Vec<ClauseId> = order
+            .iter()
+            .copied()
+            .filter(|&id| !self.clause(id).is_empty() && positive(self.clause(id)))
+            .collect();
+        let mut found: Vec<(Clause, Vec<ClauseId>)> = Vec::new();
+        let mut seen: HashSet<Clause> = HashSet::new();
+        for &nucleus in order {
+            let lits = self.clause(nucleus);
+            let candidates: Vec<Vec<ClauseId>> = lits
+                .iter()
+                .filter(|x| x.is_negative())
+                .map(|&x| {
+                    electrons
+                        .iter()
+                        .copied()
+                        .filter(|&e| self.clause(e).contains(&x.opposite()))
+                        .collect()
+                })
+                .collect();
+            if candidates.is_empty() || candidates.iter().any(|c| c.is_empty()) {
+                continue;
+            }
+            // every choice of one electron per negative literal, like an odometer
+            let mut choice = vec![0; candidates.len()];
+            loop {
+                let premises: Vec<ClauseId> = std::iter::once(nucleus)
+                    .chain(choice.iter().zip(&candidates).map(|(&k, c)| c[k]))
+                    .collect();
+                // the same premises gave the same resolvent in a previous call
+                if premises.iter().any(|&c| c >= self.resolved) {
+                    let mut c: Clause = lits
+                        .iter()
+                        .copied()
+                        .filter(|x| !x.is_negative())
+                        .collect::<Vec<_>>()
+                        .into();
+                    for (&x, &e) in lits.iter().filter(|x| x.is_negative()).zip(&premises[1..]) {
+                        for &y in self.clause(e) {
+                            if y != x.opposite() {
+                                c.insert(y);
+                            }
+                        }
+                    }
+                    if !self.contains(c.lits()) && seen.insert(c.clone()) {
+                        let empty = c.is_empty();
+                        found.push((c, premises));
+                        if empty {
+                            break;
+                        }
+                    }
+                }
+                let Some(k) = (0..choice.len())
+                    .rev()
+                    .find(|&k| choice[k] + 1 < candidates[k].len())
+                else {
+                    break;
+                };
+                choice[k] += 1;
+                choice[k + 1..].fill(0);
+            }
+            if found.last().is_some_and(|(c, _)| c.is_empty()) {
+                break;
+            }
+        }
+        let mut box_found = false;
+        for (c, premises) in found {
+            box_found = c.is_empty();
+            self.push_premises(c.lits(), Some((premises[0], premises[1])), &premises[2..]);
+        }
+        box_found
+    }
+
+    /// the positions (in the order of the round) of the clauses after `c1`, which
+    /// is at `i`, that could be resolved with it: only the ones with an opposite literal.
+    /// `marks` is a bitset of the positions, it is cleared while reading it
+    fn partners(
+        &self,
+        c1: ClauseId,
+        i: usize,
+        position: &[usize],
+        marks: &mut [u64],
+    ) -> Vec<usize> {
+        // two clauses already resolved with each other give nothing new
+        let from = if c1 < self.resolved { self.resolved } else { 0 };
+        for lit in self.clause(c1) {
+            let occurs = self.occurrences(lit.opposite());
+            let first = occurs.partition_point(|&c2| c2 < from);
+            for &c2 in &occurs[first..] {
+                let j = position[c2 as usize];
+                if j > i {
+                    marks[j / 64] |= 1 << (j % 64);
+                }
+            }
+        }
+        let mut partners = Vec::new();
+        for (w, word) in marks.iter_mut().enumerate().skip(i / 64) {
+            while *word != 0 {
+                partners.push(w * 64 + word.trailing_zeros() as usize);
+                *word &= *word - 1;
+            }
+        }
+        partners
+    }
+
+    /// the resolvent of c1 and c2, with the parents in the order they are printed
+    fn extend_solve(&self, c1: ClauseId, c2: ClauseId) -> Option<(Clause, ClauseId, ClauseId)> {
+        let (c1, c2) = if self.clause(c1).len() < self.clause(c2).len() {
             (c1, c2)
         } else {
             (c2, c1)
         };
-        let mut pp = None;
-        for atom in &c1.as_ref().c {
-            let opposite = atom.opposite();
-            if c2.c.contains(&opposite) {
-                match pp {
-                    // pruning: it is useless to have a clause like {!x, x, ...}
-                    Some(_) => return false,
-                    None => pp = Some((atom, opposite)),
-                }
+        let c = resolve(self.clause(c1), self.clause(c2))?;
+        Some((c, c1, c2))
+    }
+
+    /// a model of the clauses not eliminated, which must be saturated without
+    /// the box (see `saturate()`), up to the clauses eliminated because they
+    /// contain another one: the symbols are assigned in order, each one
+    /// so that no clause with only the symbols assigned so far becomes false
+    pub fn model(&self) -> Model {
+        let mut vars: Vec<u32> = self
+            .active()
+            .flat_map(|id| self.clause(id).iter().map(|x| x.var()))
+            .collect::<HashSet<u32>>()
+            .into_iter()
+            .collect();
+        vars.sort_by_key(|&v| Symbol::from_id(v));
+        let rank: HashMap<u32, usize> = vars.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        // the clauses by their last symbol
+        let mut last = vec![Vec::new(); vars.len()];
+        for id in self.active() {
+            if let Some(r) = self.clause(id).iter().map(|x| rank[&x.var()]).max() {
+                last[r].push(id);
             }
         }
-        let (atom, opposite) = match pp {
-            Some(x) => x,
-            None => return false,
+        let mut model = Model::new();
+        for (&v, clauses) in vars.iter().zip(last) {
+            let positive = Lit::positive(Symbol::from_id(v));
+            // if it were false one of these clauses would be false
+            let value = clauses.iter().any(|&id| {
+                let clause = self.clause(id);
+                clause.contains(&positive)
+                    && clause
+                        .iter()
+                        .all(|&x| x == positive || model.lit_value(x) == Some(false))
+            });
+            model.set(positive.symbol(), value);
+        }
+        model
+    }
+
+    /// like `model()`, for the clauses saturated by ordered resolution: the clauses
+    /// are visited from the smallest, and a clause that is false, without selected
+    /// literals and whose greatest literal is positive makes that literal true
+    pub fn model_ordered(&self, ranking: &Ranking) -> Model {
+        let key = |id: ClauseId| {
+            let mut key: Vec<usize> = self
+                .clause(id)
+                .iter()
+                .map(|&x| ranking.lit_rank(x))
+                .collect();
+            key.sort_unstable_by(|a, b| b.cmp(a));
+            key
         };
-        let new_clause: Clause =
-            c1.c.iter()
-                .filter(|x| *x != atom)
-                .chain(c2.c.iter().filter(|x| *x != &opposite))
-                .map(|x| x.clone()) // it uses Rc::clone() inside
-                .collect::<BTreeSet<Atom>>()
-                .into();
-
-        let len = new_clause.c.len();
-        // suddenly extend substitutes new values to old ones
-        if !parent.bt.contains_key(&new_clause) {
-            self.bt.insert(
-                Rc::new(new_clause),
-                Some((Rc::downgrade(&c1), Rc::downgrade(&c2))),
-            );
+        let mut clauses: Vec<(Vec<usize>, ClauseId)> =
+            self.active().map(|id| (key(id), id)).collect();
+        clauses.sort();
+        let mut model = Model::new();
+        for symbol in self.active_symbols() {
+            model.set(symbol, false);
+        }
+        for (_, id) in clauses {
+            let clause = self.clause(id);
+            if model.satisfies(clause) || ranking.selected(clause).is_some() {
+                continue;
+            }
+            if let Some(&greatest) = clause.iter().max_by_key(|&&x| ranking.lit_rank(x)) {
+                if !greatest.is_negative() {
+                    model.set_lit(greatest);
+                }
+            }
         }
-        len == 0
+        model
+    }
+
+    /// a model of the clauses not eliminated found by search, for the clause sets
+    /// saturated by a restriction without a direct construction of the model
+    pub fn model_search(&self) -> Option<Model> {
+        let clauses: Vec<&[Lit]> = self.active().map(|id| self.clause(id)).collect();
+        let mut symbols: Vec<Symbol> = self.active_symbols().collect();
+        symbols.sort();
+        symbols.dedup();
+        Model::search(&clauses, &symbols)
     }
 
     pub fn trace_from_box(&self) -> Vec<String> {
         let mut trace = vec![];
-        let empty = Rc::new(Clause::new());
-        if !self.bt.contains_key(&empty) {
-            return trace;
+        if let Some(empty) = self.find(&[]) {
+            self.trace_from(empty, &mut trace, &mut HashSet::new());
         }
-        self.trace_from(Rc::downgrade(&empty), &mut trace);
         trace
     }
 
-    fn trace_from(&self, clause: Weak<Clause>, trace: &mut Vec<String>) {
-        if let Some((c1, c2)) = self.bt.get(&clause.upgrade().unwrap()).unwrap() {
-            self.trace_from(Weak::clone(&c1), trace);
-            self.trace_from(Weak::clone(&c2), trace);
-            trace.push(format!(
-                "{}, {} -> {}",
-                c1.upgrade()
-                    .expect("self.find_box() is poorly written")
-                    .as_ref(),
-                c2.upgrade()
-                    .expect("self.find_box() is poorly written")
-                    .as_ref(),
-                clause
-                    .upgrade()
-                    .expect("self.find_box() is poorly written")
-                    .as_ref()
-            ))
+    /// a clause used more than once (e.g. an ancestor in a linear proof) is
+    /// derived only the first time
+    fn trace_from(&self, clause: ClauseId, trace: &mut Vec<String>, done: &mut HashSet<ClauseId>) {
+        let premises = self.premises(clause);
+        if premises.is_empty() || !done.insert(clause) {
+            return;
+        }
+        for &c in &premises {
+            self.trace_from(c, trace, done);
         }
+        let premises: Vec<String> = premises
+            .iter()
+            .map(|&c| Lits(self.clause(c)).to_string())
+            .collect();
+        trace.push(format!(
+            "{} -> {}",
+            premises.join(", "),
+            Lits(self.clause(clause))
+        ))
     }
 }
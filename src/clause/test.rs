@@ -1,5 +1,10 @@
-use super::SetClauses;
-use crate::{ast::Statement, context::Context, parser::Parser, slice_to_str};
+use super::{Clause, Lit, Lits, Restriction, SetClauses};
+use crate::ast::Formula;
+use crate::parser::Statement;
+use crate::symbol::Symbol;
+use crate::token::{self, Token};
+use crate::{context::Context, parser::Parser, slice_to_str};
+use std::sync::Arc;
 
 #[test]
 fn test_clauses() {
@@ -25,8 +30,8 @@ a & (b | c | (d & e & (f | g)))
         "END OF INPUT",
     ];
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
     let mut context = Context::new();
 
     for &exp in expected {
@@ -58,8 +63,8 @@ b | a;
     );
     let (buffer, expected) = test;
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
     let mut context = Context::new();
 
     let mut v = Vec::new();
@@ -86,8 +91,8 @@ fn test_prune() {
     let test = (" a | b; b | c; c | ~a; ~c | a;", "{{a, ~c}, {c, ~a}}");
     let (buffer, expected) = test;
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
     let mut context = Context::new();
 
     let mut v = Vec::new();
@@ -120,8 +125,8 @@ fn test_find_box() {
     ];
 
     for (buffer, exp) in tests {
-        let mut pars = Parser::new().unwrap();
-        pars.load_bytes(buffer.to_string()).unwrap();
+        let mut pars = Parser::new();
+        pars.load_bytes(buffer.to_string());
         let mut context = Context::new();
 
         let mut v = Vec::new();
@@ -144,7 +149,7 @@ fn test_find_box() {
         if *exp != t.find_box() {
             panic!("expected=`{exp}`\ngot     =`{}`", !exp)
         }
-    
+    }
 }
 
 #[test]
@@ -171,8 +176,8 @@ fn test_trace_from_box() {
     ];
 
     for (buffer, exp) in tests {
-        let mut pars = Parser::new().unwrap();
-        pars.load_bytes(buffer.to_string()).unwrap();
+        let mut pars = Parser::new();
+        pars.load_bytes(buffer.to_string());
         let mut context = Context::new();
 
         let mut v = Vec::new();
@@ -203,3 +208,174 @@ fn test_trace_from_box() {
         }
     }
 }
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Lit>();
+    assert_send_sync::<Clause>();
+    assert_send_sync::<SetClauses>();
+    assert_send_sync::<Token>();
+    assert_send_sync::<Formula>();
+    assert_send_sync::<Context>();
+
+    let mut pars = Parser::new();
+    pars.load_bytes("a; a => b; ~b".to_string());
+    let mut context = Context::new();
+    while let Ok(Statement::Formula(_)) = pars.parse_statement_update_context(&mut context) {}
+    let found = std::thread::spawn(move || SetClauses::from(&context).find_box())
+        .join()
+        .unwrap();
+    assert!(found);
+}
+
+#[test]
+fn test_programmatic_atoms() {
+    let mut pars = Parser::new();
+    pars.load_bytes("a => b; a".to_string());
+    let mut context = Context::new();
+    while let Ok(Statement::Formula(_)) = pars.parse_statement_update_context(&mut context) {}
+    let not_b = Formula::new_unary(token::Kind::Not, Formula::new_leaf("b".into()));
+    context.push(Arc::new(not_b)).unwrap();
+    assert!(SetClauses::from(&context).find_box());
+}
+
+#[test]
+fn test_arena() {
+    let (a, b) = (Symbol::intern("a"), Symbol::intern("b"));
+    assert_eq!(Lit::positive(a).opposite(), Lit::negative(a));
+    assert_ne!(Lit::positive(a).code(), Lit::negative(a).code());
+
+    let mut c = Clause::new();
+    assert!(c.insert(Lit::negative(b)));
+    assert!(c.insert(Lit::positive(a)));
+    assert!(c.insert(Lit::positive(a)));
+    assert!(!c.insert(Lit::positive(b)));
+    assert_eq!(c, Clause::from(vec![Lit::positive(a), Lit::negative(b)]));
+    assert_eq!(c.to_string(), "{a, ~b}");
+
+    let mut set = SetClauses::default();
+    let id = set.insert(&c);
+    assert_eq!(set.insert(&Clause::from(vec![Lit::positive(b)])), id + 1);
+    assert_eq!(set.insert(&c), id);
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.clause(id), c.lits());
+    assert_eq!(set.parents(id), None);
+    assert_eq!(set.to_string(), "{{a, ~b}, {b}}");
+    set.insert(&Clause::from(vec![Lit::negative(a)]));
+    assert!(set.find_box());
+    assert_eq!(set.to_string(), "{{}, {a}, {a, ~b}, {b}, {~a}, {~b}}");
+    let empty = set.find(&[]).unwrap();
+    let (c1, c2) = set.parents(empty).unwrap();
+    assert!(set.parents(c1).is_some() || set.parents(c2).is_some());
+}
+
+#[test]
+fn test_occurrences() {
+    let (a, b) = (Symbol::intern("a"), Symbol::intern("b"));
+    let mut set = SetClauses::default();
+    let ab = set.insert(&Clause::from(vec![Lit::positive(a), Lit::positive(b)]));
+    let na = set.insert(&Clause::from(vec![Lit::negative(a)]));
+    let anb = set.insert(&Clause::from(vec![Lit::positive(a), Lit::negative(b)]));
+    assert_eq!(set.occurrences(Lit::positive(a)), &[ab, anb]);
+    assert_eq!(set.occurrences(Lit::negative(a)), &[na]);
+    assert_eq!(set.occurrences(Lit::negative(b)), &[anb]);
+    assert!(set
+        .occurrences(Lit::positive(Symbol::intern("occurs_c")))
+        .is_empty());
+    assert!(set.find_box());
+    let na = set.find(&[Lit::negative(a)]).unwrap();
+    assert_eq!(set.occurrences(Lit::negative(a)), &[na]);
+}
+
+#[test]
+fn test_find_box_jobs() {
+    let buffer = "
+(p1 | p2) & (q1 | q2) & (r1 | r2);
+~(p1 & q1) & ~(p1 & r1) & ~(q1 & r1);
+~(p2 & q2) & ~(p2 & r2) & ~(q2 & r2);
+a | b | c; ~a | d; ~b | d; ~c | e
+";
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let mut context = Context::new();
+    while !matches!(
+        pars.parse_statement_update_context(&mut context).unwrap(),
+        Statement::Eoi
+    ) {}
+    let mut sequential = SetClauses::from(&context);
+    assert!(sequential.find_box());
+    for jobs in [2, 3, 8] {
+        let mut parallel = SetClauses::from(&context);
+        assert!(parallel.find_box_jobs(jobs));
+        assert_eq!(parallel.to_string(), sequential.to_string());
+        assert_eq!(parallel.trace_from_box(), sequential.trace_from_box());
+    }
+}
+
+#[test]
+fn test_propagate() {
+    let (a, b, c) = (
+        Symbol::intern("a"),
+        Symbol::intern("b"),
+        Symbol::intern("c"),
+    );
+    let mut set = SetClauses::default();
+    let unit = set.insert(&Clause::from(vec![Lit::positive(a)]));
+    let ab = set.insert(&Clause::from(vec![Lit::negative(a), Lit::positive(b)]));
+    let ac = set.insert(&Clause::from(vec![Lit::positive(a), Lit::positive(c)]));
+    let bc = set.insert(&Clause::from(vec![Lit::negative(b), Lit::positive(c)]));
+    assert!(!set.propagate());
+    // {a, c} contains {a}, {~a, b} and {~b, c} contain their resolvents
+    assert!(set.is_eliminated(ab) && set.is_eliminated(ac) && set.is_eliminated(bc));
+    assert!(!set.is_eliminated(unit));
+    let active: Vec<String> = set
+        .active()
+        .map(|id| Lits(set.clause(id)).to_string())
+        .collect();
+    assert_eq!(active, ["{a}", "{b}", "{c}"]);
+    assert_eq!(set.model().to_string(), "{a, b, c}");
+
+    set.insert(&Clause::from(vec![Lit::negative(c)]));
+    assert!(set.propagate());
+    assert_eq!(
+        set.trace_from_box(),
+        [
+            "{a}, {b, ~a} -> {b}",
+            "{b}, {c, ~b} -> {c}",
+            "{~c}, {c} -> {}"
+        ]
+    );
+}
+
+#[test]
+fn test_hyper() {
+    let (a, b, c) = (
+        Symbol::intern("a"),
+        Symbol::intern("b"),
+        Symbol::intern("c"),
+    );
+    let mut set = SetClauses::default();
+    let ea = set.insert(&Clause::from(vec![Lit::positive(a)]));
+    let eb = set.insert(&Clause::from(vec![Lit::positive(b), Lit::positive(c)]));
+    let nucleus = set.insert(&Clause::from(vec![
+        Lit::negative(a),
+        Lit::negative(b),
+        Lit::positive(c),
+    ]));
+    set.insert(&Clause::from(vec![Lit::negative(c)]));
+    assert!(set.saturate(1, false, &Restriction::Hyper));
+    let c_id = set.find(&[Lit::positive(c)]).unwrap();
+    assert_eq!(set.premises(c_id), [nucleus, ea, eb]);
+    assert_eq!(set.parents(c_id), Some((nucleus, ea)));
+    assert_eq!(
+        set.trace_from_box(),
+        ["{c, ~a, ~b}, {a}, {b, c} -> {c}", "{~c}, {c} -> {}"]
+    );
+    // the premises are kept with the clauses
+    let kept = set.retain_ids(|_, _| true);
+    assert_eq!(set.premises(kept[c_id as usize].unwrap()).len(), 3);
+    let mut copy = SetClauses::default();
+    copy.extend(&set);
+    assert_eq!(copy.trace_from_box(), set.trace_from_box());
+}
@@ -0,0 +1,265 @@
+use crate::error::{Error, Res};
+use crate::order::{Order, Selection};
+use crate::solver::Method;
+use crate::table;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod test;
+
+/// exit codes, following the SAT competition convention
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_ERROR: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_SAT: i32 = 10;
+pub const EXIT_UNSAT: i32 = 20;
+/// the method (e.g. `input`) missed the box, but the formulas have no model:
+/// not 0 (the convention), so a script does not take it for a success
+pub const EXIT_UNKNOWN: i32 = 30;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Input {
+    File(String),
+    Expr(String),
+    Stdin,
+}
+
+impl Input {
+    pub fn name(&self) -> &str {
+        match self {
+            Input::File(name) => name,
+            Input::Expr(_) => "<expr>",
+            Input::Stdin => "<stdin>",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format `{s}` (expected `text` or `json`)")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Text => write!(f, "text"),
+            Format::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Options {
+    inputs: Vec<Input>,
+    format: Format,
+    proof: bool,
+    quiet: bool,
+    method: Method,
+    jobs: usize,
+    preprocess: bool,
+    propagate: bool,
+    fragments: bool,
+    order: Order,
+    selection: Selection,
+    simplify: bool,
+    table_limit: usize,
+    check: bool,
+    help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            inputs: Vec::new(),
+            format: Format::Text,
+            proof: true,
+            quiet: false,
+            method: Method::default(),
+            jobs: 1,
+            preprocess: false,
+            propagate: false,
+            fragments: true,
+            order: Order::default(),
+            selection: Selection::default(),
+            simplify: true,
+            table_limit: table::LIMIT,
+            check: false,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    /// `args` should not contain the program name
+    pub fn parse(args: &[String]) -> Res<Options> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        let mut only_files = false;
+        while let Some(arg) = args.next() {
+            if only_files {
+                options.inputs.push(Input::File(arg.clone()));
+                continue;
+            }
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-e" | "--expr" => options.inputs.push(Input::Expr(value(arg, &mut args)?)),
+                "--format" => options.format = parse_value(arg, &mut args)?,
+                "--proof" => options.proof = true,
+                "--no-proof" => options.proof = false,
+                "-q" | "--quiet" => options.quiet = true,
+                "--solver" => options.method = parse_value(arg, &mut args)?,
+                "-j" | "--jobs" => options.jobs = parse_value(arg, &mut args)?,
+                "--preprocess" => options.preprocess = true,
+                "--propagate" => options.propagate = true,
+                "--no-fragments" => options.fragments = false,
+                "--order" => options.order = parse_value(arg, &mut args)?,
+                "--selection" => options.selection = parse_value(arg, &mut args)?,
+                "--no-simplify" => options.simplify = false,
+                "--table-limit" => {
+                    options.table_limit = parse_value(arg, &mut args)?;
+                    if options.table_limit > table::MAX_LIMIT {
+                        return Err(Error::Usage(format!("{arg}: at most {}", table::MAX_LIMIT)));
+                    }
+                }
+                "--check" => options.check = true,
+                "-" => options.inputs.push(Input::Stdin),
+                "--" => only_files = true,
+                _ if arg.starts_with('-') => {
+                    return Err(Error::Usage(format!("unknown option `{arg}`")))
+                }
+                _ => options.inputs.push(Input::File(arg.clone())),
+            }
+        }
+        Ok(options)
+    }
+
+    /// stdin is read as a file when nothing else is given and it is not a terminal
+    pub fn resolve_stdin(&mut self, stdin_is_terminal: bool) {
+        if self.inputs.is_empty() && !stdin_is_terminal {
+            self.inputs.push(Input::Stdin);
+        }
+    }
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs
+    }
+    pub fn format(&self) -> Format {
+        self.format
+    }
+    pub fn proof(&self) -> bool {
+        self.proof
+    }
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+    pub fn method(&self) -> Method {
+        self.method
+    }
+    /// the number of threads used to solve, 0 means one for each core
+    pub fn jobs(&self) -> usize {
+        match self.jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+    /// eliminate symbols before resolving
+    pub fn preprocess(&self) -> bool {
+        self.preprocess
+    }
+    /// propagate the unit clauses before each round of resolution
+    pub fn propagate(&self) -> bool {
+        self.propagate
+    }
+    /// solve Horn and 2-CNF clauses with their linear-time algorithms
+    pub fn fragments(&self) -> bool {
+        self.fragments
+    }
+    /// the ordering of ordered resolution
+    pub fn order(&self) -> Order {
+        Order::new(self.order.atoms().to_vec(), self.selection)
+    }
+    /// simplify the formulas before the conversion to clauses
+    pub fn simplify(&self) -> bool {
+        self.simplify
+    }
+    /// the most atoms of a truth table, also to minimize a formula
+    pub fn table_limit(&self) -> usize {
+        self.table_limit
+    }
+    /// only parse the input, without solving
+    pub fn check(&self) -> bool {
+        self.check
+    }
+    pub fn help(&self) -> bool {
+        self.help
+    }
+    pub fn interactive(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+fn value<'a>(flag: &str, args: &mut impl Iterator<Item = &'a String>) -> Res<String> {
+    match args.next() {
+        Some(v) => Ok(v.clone()),
+        None => Err(Error::Usage(format!("`{flag}` expects a value"))),
+    }
+}
+
+fn parse_value<'a, T>(flag: &str, args: &mut impl Iterator<Item = &'a String>) -> Res<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value(flag, args)?
+        .parse()
+        .map_err(|err| Error::Usage(format!("{flag}: {err}")))
+}
+
+pub fn usage(program: &str) -> String {
+    format!(
+        "\
+Usage: {program} [options] [file ...]
+With no input the program starts the REPL (or reads stdin if it is not a terminal).
+Options:
+    -e, --expr <formula>   evaluate <formula> (can be repeated)
+    -                      read from stdin
+    --format <text|json>   output format (default: text)
+    --proof, --no-proof    print (or not) the proof when a box is found (or a model if not)
+    -q, --quiet            print only errors, the result is the exit code
+    --solver <method>      solving method, also after `!` (default: {}):
+                           {}
+    -j, --jobs <n>         threads used to solve, 0 for one per core (default: 1)
+    --preprocess           eliminate symbols (Davis-Putnam) before resolving
+    --propagate            propagate the unit clauses before each round of resolution
+    --no-fragments         always use resolution, also for Horn and 2-CNF clauses
+    --order <atoms>        ordering of `ordered`, from the greatest: `c>b>a`
+                           (the other atoms are smaller, `a < b`)
+    --selection <none|negative>
+                           select the greatest negative literal with `ordered`
+    --no-simplify          convert the formulas to clauses as they are, without simplifying
+    --table-limit <n>      most atoms of `table` and `minimize` (default: {}, at most {})
+    --check                only look for errors in the input, without solving
+    -h, --help             print this message
+Exit codes:
+    {EXIT_SAT}: satisfiable, {EXIT_UNSAT}: unsatisfiable, {EXIT_ERROR}: invalid input, {EXIT_USAGE}: invalid arguments
+    {EXIT_UNKNOWN}: unknown, the solver (`linear` or `input`) missed the box
+    with `--check`: {EXIT_OK}: valid input, {EXIT_ERROR}: invalid input",
+        Method::default(),
+        Method::ALL.map(|m| m.as_str().to_string()).join(", "),
+        table::LIMIT,
+        table::MAX_LIMIT
+    )
+}
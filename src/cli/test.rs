@@ -0,0 +1,80 @@
+use super::{Format, Input, Options};
+use crate::solver::Method;
+
+#[test]
+fn test_options() {
+    let args: Vec<String> = [
+        "a.txt",
+        "-e",
+        "a & b",
+        "-",
+        "--format",
+        "json",
+        "--no-proof",
+        "-q",
+        "--solver",
+        "resolution",
+        "-j",
+        "3",
+        "--preprocess",
+        "--propagate",
+        "--no-fragments",
+        "--no-simplify",
+        "--table-limit",
+        "4",
+        "--check",
+        "--",
+        "-b.txt",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let options = Options::parse(&args).unwrap();
+    let expected = &[
+        Input::File("a.txt".to_string()),
+        Input::Expr("a & b".to_string()),
+        Input::Stdin,
+        Input::File("-b.txt".to_string()),
+    ];
+    assert_eq!(options.inputs(), expected);
+    assert_eq!(options.format(), Format::Json);
+    assert!(!options.proof());
+    assert!(options.quiet());
+    assert_eq!(options.method(), Method::Resolution);
+    assert_eq!(options.jobs(), 3);
+    assert!(options.preprocess());
+    assert!(options.propagate());
+    assert!(!options.fragments());
+    assert!(!options.simplify());
+    assert_eq!(options.table_limit(), 4);
+    assert!(options.check());
+    assert!(!options.interactive());
+}
+
+#[test]
+fn test_options_err() {
+    let tests: &[&[&str]] = &[
+        &["--bogus"],
+        &["-e"],
+        &["--format", "xml"],
+        &["--solver", "x"],
+        &["--jobs", "-1"],
+        &["--table-limit", "x"],
+        &["--table-limit", "21"],
+    ];
+    for args in tests {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        if let Ok(options) = Options::parse(&args) {
+            panic!("expected an error for {args:?}, got {options:?}")
+        }
+    }
+}
+
+#[test]
+fn test_options_stdin() {
+    let mut options = Options::parse(&[]).unwrap();
+    options.resolve_stdin(true);
+    assert!(options.interactive());
+    options.resolve_stdin(false);
+    assert_eq!(options.inputs(), &[Input::Stdin]);
+}
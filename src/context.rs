@@ -1,56 +1,115 @@
 use crate::ast::Formula;
-use crate::clause::SetClauses;
-use crate::error::{IndexOutOfBound, Res};
-use std::rc::Rc;
+use crate::ast::Simplified;
+use crate::clause::{Clause, SetClauses};
+use crate::error::{Error, Res};
+use crate::simplify::Simplification;
+use crate::solver::{Solver, Tag};
+use std::sync::Arc;
 
 pub struct InnerContext {
-    formula: Rc<Formula>,
-    set_clauses: Rc<SetClauses>,
+    formula: Arc<Formula>,
+    set_clauses: Arc<SetClauses>,
+    /// the clauses of the formula in the solver of the context
+    tag: Tag,
 }
 
 impl InnerContext {
-    fn new(formula: Rc<Formula>) -> Res<InnerContext> {
-        let dist = formula.as_ref().clone().distribute()?;
-        let set_clauses = Rc::new((&dist).into());
+    /// the formula is simplified before the conversion to clauses if `simplify`
+    fn new(formula: Arc<Formula>, tag: Tag, simplify: bool) -> Res<InnerContext> {
+        let simplified = if simplify {
+            Simplification::new(&formula).result().clone()
+        } else {
+            Simplified::Formula(formula.as_ref().clone())
+        };
+        let set_clauses = match simplified {
+            Simplified::Formula(f) => Arc::new((&f.distribute()?).into()),
+            Simplified::Constant(true) => Arc::new(SetClauses::default()),
+            Simplified::Constant(false) => {
+                let mut set = SetClauses::default();
+                set.insert(&Clause::new());
+                Arc::new(set)
+            }
+        };
         Ok(InnerContext {
             formula,
             set_clauses,
+            tag,
         })
     }
-    pub fn formula(&self) -> Rc<Formula> {
-        Rc::clone(&self.formula)
+    pub fn formula(&self) -> Arc<Formula> {
+        Arc::clone(&self.formula)
     }
-    pub fn set_clauses(&self) -> Rc<SetClauses> {
-        Rc::clone(&self.set_clauses)
+    pub fn set_clauses(&self) -> Arc<SetClauses> {
+        Arc::clone(&self.set_clauses)
     }
 }
 
+/// the formulas, and what has been derived from them by the previous `solve()`:
+/// removing a formula forgets only what depends on it
 pub struct Context {
     inner: Vec<InnerContext>,
+    solver: Solver,
+    next_tag: Tag,
+    /// simplify the formulas before the conversion to clauses
+    simplify: bool,
+}
+
+impl Default for Context {
+    fn default() -> Context {
+        Context {
+            inner: Vec::new(),
+            solver: Solver::default(),
+            next_tag: Tag::default(),
+            simplify: true,
+        }
+    }
 }
 
 impl Context {
     pub fn new() -> Context {
-        Context { inner: Vec::new() }
+        Context::default()
+    }
+    /// for the formulas pushed from now on
+    pub fn set_simplify(&mut self, simplify: bool) {
+        self.simplify = simplify;
     }
-    pub fn push(&mut self, formula: Rc<Formula>) -> Res<()> {
-        self.inner.push(InnerContext::new(formula)?);
+    pub fn push(&mut self, formula: Arc<Formula>) -> Res<()> {
+        self.inner
+            .push(InnerContext::new(formula, self.next_tag, self.simplify)?);
+        self.next_tag += 1;
         Ok(())
     }
     pub fn remove(&mut self, index: usize) -> Res<InnerContext> {
         if index < self.inner.len() {
-            Ok(self.inner.remove(index))
+            let removed = self.inner.remove(index);
+            self.solver.remove_tagged(removed.tag);
+            Ok(removed)
         } else {
-            Err(IndexOutOfBound::new(format!(
-                "{} >= {} (number of formulas)",
-                index,
-                self.inner.len()
-            )))
+            Err(Error::index_out_of_bound(index, self.inner.len(), None))
         }
     }
     pub fn inner(&self) -> &Vec<InnerContext> {
         &self.inner
     }
+
+    /// returns true if the box is found, resolving only what was not already
+    /// resolved by the previous calls
+    pub fn solve(&mut self) -> bool {
+        for f in &self.inner {
+            if !self.solver.has_tagged(f.tag) {
+                self.solver.add_tagged(f.tag, &f.set_clauses);
+            }
+        }
+        self.solver.solve()
+    }
+    /// the clauses of the formulas and the ones derived from them so far
+    pub fn solver(&self) -> &Solver {
+        &self.solver
+    }
+    /// to change how the next `solve()` works
+    pub fn solver_mut(&mut self) -> &mut Solver {
+        &mut self.solver
+    }
     pub fn vec_str(&self) -> Vec<String> {
         self.inner
             .iter()
@@ -0,0 +1,506 @@
+use crate::ast::Formula;
+use crate::model::Model;
+use crate::symbol::Symbol;
+use crate::tableau::Tableau;
+use crate::token::Kind;
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// the goals tried before giving up, see `Proof::search()`
+pub const LIMIT: usize = 20_000;
+
+/// a formula of a proof, or the contradiction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prop {
+    Atom(Symbol),
+    Not(Box<Prop>),
+    Binary(Kind, Box<Prop>, Box<Prop>),
+    Bottom,
+}
+
+impl Prop {
+    fn not(p: &Prop) -> Prop {
+        Prop::Not(Box::new(p.clone()))
+    }
+    fn binary(l: &Prop, operator: Kind, r: &Prop) -> Prop {
+        Prop::Binary(operator, Box::new(l.clone()), Box::new(r.clone()))
+    }
+}
+
+impl From<&Formula> for Prop {
+    fn from(f: &Formula) -> Prop {
+        match f {
+            Formula::Leaf(leaf) => Prop::Atom(leaf.symbol()),
+            Formula::Unary(unary) => Prop::Not(Box::new(unary.right().into())),
+            Formula::Binary(binary) => {
+                let (l, operator, r) = binary.parts();
+                Prop::Binary(operator, Box::new(l.into()), Box::new(r.into()))
+            }
+        }
+    }
+}
+
+/// like `Formula`, the contradiction is `_|_`
+impl fmt::Display for Prop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Prop::Atom(s) => write!(f, "{s}"),
+            Prop::Not(p) => write!(f, "(~{p})"),
+            Prop::Binary(operator, l, r) => write!(f, "({l} {operator} {r})"),
+            Prop::Bottom => write!(f, "_|_"),
+        }
+    }
+}
+
+/// the rules of natural deduction, `I` introduces and `E` eliminates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Premise,
+    Assumption,
+    /// a line of an outer subproof
+    Reiteration,
+    AndI,
+    AndE,
+    OrI,
+    OrE,
+    ImpliesI,
+    ImpliesE,
+    EquivI,
+    EquivE,
+    NotI,
+    NotE,
+    DoubleNotE,
+    /// anything follows from the contradiction
+    BottomE,
+    /// the negation of the formula leads to the contradiction
+    Indirect,
+}
+
+impl Rule {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Rule::Premise => "premise",
+            Rule::Assumption => "assumption",
+            Rule::Reiteration => "R",
+            Rule::AndI => "&I",
+            Rule::AndE => "&E",
+            Rule::OrI => "|I",
+            Rule::OrE => "|E",
+            Rule::ImpliesI => "=>I",
+            Rule::ImpliesE => "=>E",
+            Rule::EquivI => "<=>I",
+            Rule::EquivE => "<=>E",
+            Rule::NotI => "~I",
+            Rule::NotE => "~E",
+            Rule::DoubleNotE => "~~E",
+            Rule::BottomE => "_|_E",
+            Rule::Indirect => "IP",
+        }
+    }
+}
+
+/// what a rule is applied to, as indices of the lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ref {
+    Line(usize),
+    /// from the assumption to the last line of the subproof
+    Subproof(usize, usize),
+}
+
+/// a line is in `depth` subproofs
+#[derive(Debug, Clone)]
+pub struct Line {
+    prop: Prop,
+    depth: usize,
+    rule: Rule,
+    refs: Vec<Ref>,
+}
+
+impl Line {
+    pub fn prop(&self) -> &Prop {
+        &self.prop
+    }
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    pub fn refs(&self) -> &[Ref] {
+        &self.refs
+    }
+}
+
+/// why there is no proof
+#[derive(Debug, Clone)]
+pub enum Failure {
+    /// the premises do not entail the goal: the model is a counterexample
+    Invalid(Model),
+    /// the goals tried were more than the limit
+    Limit(usize),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Invalid(model) => write!(f, "not entailed, counterexample {model}"),
+            Failure::Limit(limit) => write!(
+                f,
+                "no proof found within {limit} goals, the entailment holds (see `tableau`)"
+            ),
+        }
+    }
+}
+
+/// a Fitch-style natural deduction proof: the premises, then every line
+/// follows from the previous ones; the last one is the goal
+#[derive(Debug, Clone)]
+pub struct Proof {
+    lines: Vec<Line>,
+}
+
+impl Proof {
+    /// looks for a proof of `goal` from `premises`, first with the shortest
+    /// chains of subgoals; the lines not needed are removed. The entailment is
+    /// checked with a tableau before searching
+    pub fn search(premises: &[Formula], goal: &Formula, limit: usize) -> Result<Proof, Failure> {
+        let mut formulas = premises.to_vec();
+        formulas.push(Formula::new_unary(Kind::Not, goal.clone()));
+        if let Some(model) = Tableau::new(&formulas).model() {
+            return Err(Failure::Invalid(model.clone()));
+        }
+        let goal = Prop::from(goal);
+        let mut steps = 0;
+        for depth in 1.. {
+            let mut search = Search {
+                lines: Vec::new(),
+                available: Vec::new(),
+                depth: 0,
+                steps,
+                limit,
+                goals: Vec::new(),
+            };
+            for p in premises {
+                search.push(p.into(), Rule::Premise, Vec::new());
+            }
+            if let Some(end) = search.prove(&goal, depth) {
+                return Ok(Proof::prune(search.lines, end));
+            }
+            if search.steps >= limit {
+                return Err(Failure::Limit(limit));
+            }
+            steps = search.steps;
+        }
+        unreachable!("the depth grows until the limit")
+    }
+
+    /// only the premises and the lines needed for `end`, which becomes the last one
+    fn prune(mut lines: Vec<Line>, end: usize) -> Proof {
+        let end = if end + 1 == lines.len() && lines[end].rule != Rule::Premise {
+            end
+        } else {
+            lines.push(Line {
+                prop: lines[end].prop.clone(),
+                depth: 0,
+                rule: Rule::Reiteration,
+                refs: vec![Ref::Line(end)],
+            });
+            lines.len() - 1
+        };
+        let mut used: Vec<bool> = lines.iter().map(|l| l.rule == Rule::Premise).collect();
+        let mut stack = vec![end];
+        while let Some(i) = stack.pop() {
+            used[i] = true;
+            for r in &lines[i].refs {
+                match *r {
+                    Ref::Line(j) => stack.push(j),
+                    Ref::Subproof(a, e) => stack.extend([a, e]),
+                }
+            }
+        }
+        let mut index = vec![0; lines.len()];
+        let mut kept = Vec::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            if used[i] {
+                index[i] = kept.len();
+                kept.push(line);
+            }
+        }
+        for line in &mut kept {
+            for r in &mut line.refs {
+                *r = match *r {
+                    Ref::Line(j) => Ref::Line(index[j]),
+                    Ref::Subproof(a, e) => Ref::Subproof(index[a], index[e]),
+                };
+            }
+        }
+        Proof { lines: kept }
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+/// a proof being searched
+struct Search {
+    lines: Vec<Line>,
+    /// the lines that can be used, in order: not in a closed subproof
+    available: Vec<usize>,
+    depth: usize,
+    steps: usize,
+    limit: usize,
+    /// the goals being proved and the lines available then, to avoid loops
+    goals: Vec<(Prop, usize)>,
+}
+
+impl Search {
+    fn find(&self, p: &Prop) -> Option<usize> {
+        self.available
+            .iter()
+            .rev()
+            .copied()
+            .find(|&i| self.lines[i].prop == *p)
+    }
+    fn push(&mut self, prop: Prop, rule: Rule, refs: Vec<Ref>) -> usize {
+        self.lines.push(Line {
+            prop,
+            depth: self.depth,
+            rule,
+            refs,
+        });
+        self.available.push(self.lines.len() - 1);
+        self.lines.len() - 1
+    }
+    /// adds `prop` if not there
+    fn derive(&mut self, prop: Prop, rule: Rule, refs: Vec<Ref>) -> bool {
+        if self.find(&prop).is_some() {
+            return false;
+        }
+        self.push(prop, rule, refs);
+        true
+    }
+
+    /// proves `goal` in a subproof from `assumption`, returns the first and
+    /// the last line; the subproof is closed anyway
+    fn subproof(&mut self, assumption: Prop, goal: &Prop, depth: usize) -> Option<Ref> {
+        self.depth += 1;
+        let first = self.push(assumption, Rule::Assumption, Vec::new());
+        let mut last = self.prove(goal, depth);
+        if let Some(end) = last.filter(|&end| end < first) {
+            last = Some(self.push(goal.clone(), Rule::Reiteration, vec![Ref::Line(end)]));
+        }
+        self.available.retain(|&i| i < first);
+        self.depth -= 1;
+        Some(Ref::Subproof(first, last?))
+    }
+
+    /// the eliminations that do not need a subgoal, until nothing new is found
+    fn saturate(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for k in 0..self.available.len() {
+                let i = self.available[k];
+                let prop = self.lines[i].prop.clone();
+                changed |= match &prop {
+                    Prop::Binary(Kind::And, l, r) => {
+                        let left = self.derive(*l.clone(), Rule::AndE, vec![Ref::Line(i)]);
+                        self.derive(*r.clone(), Rule::AndE, vec![Ref::Line(i)]) || left
+                    }
+                    Prop::Binary(Kind::Equiv, l, r) => {
+                        let refs = vec![Ref::Line(i)];
+                        let left =
+                            self.derive(Prop::binary(l, Kind::Implies, r), Rule::EquivE, refs);
+                        let refs = vec![Ref::Line(i)];
+                        self.derive(Prop::binary(r, Kind::Implies, l), Rule::EquivE, refs) || left
+                    }
+                    Prop::Binary(Kind::Implies, l, r) => match self.find(l) {
+                        Some(j) => self.derive(
+                            *r.clone(),
+                            Rule::ImpliesE,
+                            vec![Ref::Line(i), Ref::Line(j)],
+                        ),
+                        None => false,
+                    },
+                    Prop::Not(p) => match (p.as_ref(), self.find(p)) {
+                        (Prop::Not(q), _) => {
+                            self.derive(*q.clone(), Rule::DoubleNotE, vec![Ref::Line(i)])
+                        }
+                        (_, Some(j)) => {
+                            self.derive(Prop::Bottom, Rule::NotE, vec![Ref::Line(j), Ref::Line(i)])
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                };
+            }
+        }
+    }
+
+    /// returns the line of `goal`, at most `depth` subgoals away; if there is
+    /// none the lines added are removed
+    fn prove(&mut self, goal: &Prop, depth: usize) -> Option<usize> {
+        if let Some(i) = self.find(goal) {
+            return Some(i);
+        }
+        let key = (goal.clone(), self.available.len());
+        if depth == 0 || self.steps >= self.limit || self.goals.contains(&key) {
+            return None;
+        }
+        self.steps += 1;
+        let mark = (self.lines.len(), self.available.len());
+        self.goals.push(key);
+        let found = self.prove_inner(goal, depth - 1);
+        self.goals.pop();
+        if found.is_none() {
+            self.lines.truncate(mark.0);
+            self.available.truncate(mark.1);
+        }
+        found
+    }
+
+    fn prove_inner(&mut self, goal: &Prop, depth: usize) -> Option<usize> {
+        self.saturate();
+        if let Some(i) = self.find(goal) {
+            return Some(i);
+        }
+        if let Some(i) = self.find(&Prop::Bottom) {
+            return Some(self.push(goal.clone(), Rule::BottomE, vec![Ref::Line(i)]));
+        }
+        // the introductions that can always be tried first
+        match goal {
+            Prop::Binary(Kind::And, l, r) => {
+                let i = self.prove(l, depth)?;
+                let j = self.prove(r, depth)?;
+                return Some(self.push(goal.clone(), Rule::AndI, vec![Ref::Line(i), Ref::Line(j)]));
+            }
+            Prop::Binary(Kind::Implies, l, r) => {
+                let s = self.subproof(*l.clone(), r, depth)?;
+                return Some(self.push(goal.clone(), Rule::ImpliesI, vec![s]));
+            }
+            Prop::Binary(Kind::Equiv, l, r) => {
+                let s1 = self.subproof(*l.clone(), r, depth)?;
+                let s2 = self.subproof(*r.clone(), l, depth)?;
+                return Some(self.push(goal.clone(), Rule::EquivI, vec![s1, s2]));
+            }
+            Prop::Not(p) => {
+                let s = self.subproof(*p.clone(), &Prop::Bottom, depth)?;
+                return Some(self.push(goal.clone(), Rule::NotI, vec![s]));
+            }
+            _ => {}
+        }
+        let mark = (self.lines.len(), self.available.len());
+        let undo = |search: &mut Search| {
+            search.lines.truncate(mark.0);
+            search.available.truncate(mark.1);
+        };
+        // the disjunctions, by cases
+        for k in 0..self.available.len() {
+            let i = self.available[k];
+            let Prop::Binary(Kind::Or, l, r) = self.lines[i].prop.clone() else {
+                continue;
+            };
+            if self.find(&l).is_some() || self.find(&r).is_some() {
+                continue;
+            }
+            if let Some(s1) = self.subproof(*l, goal, depth) {
+                if let Some(s2) = self.subproof(*r, goal, depth) {
+                    let refs = vec![Ref::Line(i), s1, s2];
+                    return Some(self.push(goal.clone(), Rule::OrE, refs));
+                }
+            }
+            undo(self);
+        }
+        // the implications with the goal as consequent
+        for k in 0..self.available.len() {
+            let i = self.available[k];
+            let Prop::Binary(Kind::Implies, l, r) = self.lines[i].prop.clone() else {
+                continue;
+            };
+            if *r == *goal {
+                if let Some(j) = self.prove(&l, depth) {
+                    return Some(self.push(
+                        goal.clone(),
+                        Rule::ImpliesE,
+                        vec![Ref::Line(i), Ref::Line(j)],
+                    ));
+                }
+            }
+        }
+        if let Prop::Binary(Kind::Or, l, r) = goal {
+            for p in [l, r] {
+                if let Some(i) = self.prove(p, depth) {
+                    return Some(self.push(goal.clone(), Rule::OrI, vec![Ref::Line(i)]));
+                }
+            }
+        }
+        if *goal == Prop::Bottom {
+            // a formula whose negation is there
+            for k in 0..self.available.len() {
+                let i = self.available[k];
+                let Prop::Not(p) = self.lines[i].prop.clone() else {
+                    continue;
+                };
+                if let Some(j) = self.prove(&p, depth) {
+                    return Some(self.push(
+                        Prop::Bottom,
+                        Rule::NotE,
+                        vec![Ref::Line(j), Ref::Line(i)],
+                    ));
+                }
+            }
+            return None;
+        }
+        let s = self.subproof(Prop::not(goal), &Prop::Bottom, depth)?;
+        Some(self.push(goal.clone(), Rule::Indirect, vec![s]))
+    }
+}
+
+/// the lines are numbered from 1, a subproof is inside one more `|` and its
+/// assumption is underlined, like the premises
+impl fmt::Display for Proof {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let number = self.lines.len().to_string().len();
+        let text: Vec<String> = self
+            .lines
+            .iter()
+            .map(|l| format!("{}{}", "| ".repeat(l.depth + 1), l.prop))
+            .collect();
+        let width = text.iter().map(|t| t.len()).max().unwrap_or(0);
+        for (i, (line, text)) in self.lines.iter().zip(&text).enumerate() {
+            let refs: Vec<String> = line
+                .refs
+                .iter()
+                .map(|r| match r {
+                    Ref::Line(j) => (j + 1).to_string(),
+                    Ref::Subproof(a, e) => format!("{}-{}", a + 1, e + 1),
+                })
+                .collect();
+            let justification = format!("{} {}", line.rule.as_str(), refs.join(", "));
+            writeln!(
+                f,
+                "{:>number$} {text:width$}  {}",
+                i + 1,
+                justification.trim_end()
+            )?;
+            let last_premise = line.rule == Rule::Premise
+                && self
+                    .lines
+                    .get(i + 1)
+                    .is_none_or(|next| next.rule != Rule::Premise);
+            if line.rule == Rule::Assumption || last_premise {
+                writeln!(f, "{:number$} {}|---", "", "| ".repeat(line.depth))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use super::{Failure, Proof, Prop, Ref, Rule, LIMIT};
+use crate::testutil::formulas;
+use crate::token::Kind;
+
+/// panics if a line does not follow from its references by its rule
+fn check(proof: &Proof, goal: &Prop) {
+    let lines = proof.lines();
+    let prop = |j: usize| lines[j].prop();
+    // `j` is in the scope of `i` if no subproof with `j` has been closed
+    let visible = |j: usize, i: usize| {
+        j < i
+            && lines[j + 1..=i].iter().all(|l| {
+                l.depth() > lines[j].depth()
+                    || (l.depth() == lines[j].depth() && l.rule() != Rule::Assumption)
+            })
+    };
+    let subproof = |r: &Ref, i: usize| match *r {
+        Ref::Subproof(a, e) => {
+            assert_eq!(lines[a].rule(), Rule::Assumption);
+            assert_eq!(lines[a].depth(), lines[i].depth() + 1);
+            assert!(a == e || visible(a, e), "{a}-{e}");
+            assert!(e < i);
+            (prop(a).clone(), prop(e).clone())
+        }
+        Ref::Line(_) => panic!("line {i}: not a subproof"),
+    };
+    let binary = |p: &Prop, kind: Kind| match p {
+        Prop::Binary(k, l, r) if *k == kind => (*l.clone(), *r.clone()),
+        _ => panic!("{p} is not {kind}"),
+    };
+    for (i, line) in lines.iter().enumerate() {
+        let refs: Vec<Prop> = line
+            .refs()
+            .iter()
+            .filter_map(|r| match *r {
+                Ref::Line(j) => {
+                    assert!(visible(j, i), "line {}: {} not visible", i + 1, j + 1);
+                    Some(prop(j).clone())
+                }
+                Ref::Subproof(..) => None,
+            })
+            .collect();
+        let p = line.prop().clone();
+        let not = |p: &Prop| Prop::Not(Box::new(p.clone()));
+        match line.rule() {
+            Rule::Premise => assert!(line.depth() == 0 && i < lines.len() - 1),
+            Rule::Assumption => assert!(line.depth() > 0),
+            Rule::Reiteration => assert_eq!(refs, [p]),
+            Rule::AndI => assert_eq!(binary(&p, Kind::And), (refs[0].clone(), refs[1].clone())),
+            Rule::AndE => {
+                let (l, r) = binary(&refs[0], Kind::And);
+                assert!(p == l || p == r);
+            }
+            Rule::OrI => {
+                let (l, r) = binary(&p, Kind::Or);
+                assert!(refs[0] == l || refs[0] == r);
+            }
+            Rule::OrE => {
+                let (l, r) = binary(&refs[0], Kind::Or);
+                assert_eq!(subproof(&line.refs()[1], i), (l, p.clone()));
+                assert_eq!(subproof(&line.refs()[2], i), (r, p));
+            }
+            Rule::ImpliesI => {
+                assert_eq!(subproof(&line.refs()[0], i), binary(&p, Kind::Implies))
+            }
+            Rule::ImpliesE => assert_eq!(binary(&refs[0], Kind::Implies), (refs[1].clone(), p)),
+            Rule::EquivI => {
+                let (l, r) = binary(&p, Kind::Equiv);
+                assert_eq!(subproof(&line.refs()[0], i), (l.clone(), r.clone()));
+                assert_eq!(subproof(&line.refs()[1], i), (r, l));
+            }
+            Rule::EquivE => {
+                let (l, r) = binary(&refs[0], Kind::Equiv);
+                let (a, b) = binary(&p, Kind::Implies);
+                assert!((a == l && b == r) || (a == r && b == l));
+            }
+            Rule::NotI => {
+                let (a, e) = subproof(&line.refs()[0], i);
+                assert_eq!((not(&a), e), (p, Prop::Bottom));
+            }
+            Rule::NotE => {
+                assert_eq!(refs[1], not(&refs[0]));
+                assert_eq!(p, Prop::Bottom);
+            }
+            Rule::DoubleNotE => assert_eq!(refs[0], not(&not(&p))),
+            Rule::BottomE => assert_eq!(refs[0], Prop::Bottom),
+            Rule::Indirect => {
+                let (a, e) = subproof(&line.refs()[0], i);
+                assert_eq!((a, e), (not(&p), Prop::Bottom));
+            }
+        }
+    }
+    let last = lines.last().unwrap();
+    assert_eq!((last.prop(), last.depth()), (goal, 0));
+}
+
+#[test]
+fn test_deduction() {
+    let tests = [
+        ("a => b; b => c", "a => c"),
+        ("a & b", "b & a"),
+        ("a | b", "b | a"),
+        ("", "a | ~a"),
+        ("", "~~a => a"),
+        ("a <=> b", "b <=> a"),
+        ("~(a | b)", "~a & ~b"),
+        ("~(a & b)", "~a | ~b"),
+        ("a | b; ~a", "b"),
+        ("a; ~a", "c"),
+        ("(a => b) => a", "a"),
+        ("a => (b => c)", "(a & b) => c"),
+        ("", "(a => b) | (b => a)"),
+        ("a", "a"),
+    ];
+    for (premises, goal) in tests {
+        let proof = Proof::search(&formulas(premises), &formulas(goal)[0], LIMIT)
+            .unwrap_or_else(|err| panic!("{premises} |- {goal}: {err}"));
+        check(&proof, &Prop::from(&formulas(goal)[0]));
+    }
+}
+
+#[test]
+fn test_deduction_output() {
+    let proof = Proof::search(&formulas("a => b; b => c"), &formulas("a => c")[0], LIMIT).unwrap();
+    assert_eq!(
+        proof.to_string(),
+        "\
+1 | (a => b)  premise
+2 | (b => c)  premise
+  |---
+3 | | a       assumption
+  | |---
+4 | | b       =>E 1, 3
+5 | | c       =>E 2, 4
+6 | (a => c)  =>I 3-5
+"
+    );
+}
+
+#[test]
+fn test_deduction_failure() {
+    let err = Proof::search(&formulas("a | b"), &formulas("a")[0], LIMIT).unwrap_err();
+    assert!(matches!(err, Failure::Invalid(_)));
+    assert_eq!(err.to_string(), "not entailed, counterexample {~a, b}");
+    let err = Proof::search(&formulas(""), &formulas("(a => b) | (b => a)")[0], 2).unwrap_err();
+    assert!(matches!(err, Failure::Limit(2)));
+}
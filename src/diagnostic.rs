@@ -0,0 +1,102 @@
+use crate::error::Error;
+use crate::solver::Method;
+use crate::token::Kind;
+
+#[cfg(test)]
+mod test;
+
+/// renders `err` followed, if `line` (the source line the error is in) is known,
+/// by the line itself with the offending token underlined and a note:
+/// ```text
+/// Parse error [2:3]: got=`!` (Bang): not the beginning of a formula
+///   |
+/// 2 | a & !b
+///   |     ^ use `~` for negation
+/// ```
+pub fn render(err: &Error, input: &str, line: Option<&str>) -> String {
+    let mut s = if input.is_empty() {
+        err.to_string()
+    } else {
+        format!("{input}: {err}")
+    };
+    let note = note(err);
+    match (err.span(), line) {
+        (Some(span), Some(line)) => {
+            let start = span.start();
+            let row = start.row().to_string();
+            let pad = " ".repeat(row.len());
+            let first = start.col().saturating_sub(1);
+            let last = if span.end().row() == start.row() {
+                span.end().col().saturating_sub(1).max(first + 1)
+            } else {
+                line.len().max(first + 1)
+            };
+            let indent: String = line
+                .bytes()
+                .take(first)
+                .map(|c| if c == b'\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = "^".repeat(last - first);
+            s.push_str(&format!(
+                "\n{pad} |\n{row} | {line}\n{pad} | {indent}{carets}"
+            ));
+            if let Some(note) = note {
+                s.push_str(&format!(" {note}"));
+            }
+        }
+        _ => {
+            if let Some(note) = note {
+                s.push_str(&format!("\nnote: {note}"));
+            }
+        }
+    }
+    s
+}
+
+/// a hint about how to fix the error
+pub fn note(err: &Error) -> Option<String> {
+    let note = match err {
+        Error::InvalidToken { literal, .. } => {
+            if literal.starts_with('=') {
+                "did you mean `=>`?"
+            } else if literal.starts_with('<') {
+                "did you mean `<=>`?"
+            } else if literal.starts_with('_') {
+                "identifiers must begin with a letter"
+            } else {
+                "not a valid token, type `help` for the syntax"
+            }
+        }
+        Error::UnexpectedToken {
+            found, expected, ..
+        } => match (found, expected.as_slice()) {
+            (Kind::Bang, _) => {
+                return Some(format!(
+                    "`!` begins a statement, alone or as `! <method>` with one of {}; \
+                     use `~` for negation",
+                    Method::names()
+                ))
+            }
+            (Kind::Greater, [Kind::Separator]) => "did you mean `=>`?",
+            (_, [Kind::ParenR]) => "this `(` is never closed",
+            (_, [Kind::Number]) => "`-` must be followed by the index of a formula",
+            (Kind::Identifier | Kind::Number | Kind::ParenL | Kind::Not, _) => {
+                "there is no operator before this formula"
+            }
+            _ => "a statement ends with `;` or a new line",
+        },
+        Error::ExpectedFormula { found, .. } => match found {
+            Kind::Eoi | Kind::Separator => "the formula is not complete",
+            Kind::Bang => "use `~` for negation",
+            Kind::ParenR => "there is nothing inside the parenthesis",
+            _ => "a formula begins with `~`, `(`, an identifier or the index of a formula",
+        },
+        Error::InvalidNumber { .. } => "the index is too big",
+        Error::UnknownMethod { .. } => "`!` can be followed by a method, type `help` for the list",
+        Error::RepeatedAtom { .. } => "each atom can be given once, from the greatest",
+        Error::IndexOutOfBound { .. } => "use `?` to list the formulas",
+        Error::Usage(_) => "use `--help` to list the options",
+        Error::Io(_) => return None,
+    };
+    Some(note.to_string())
+}
@@ -0,0 +1,63 @@
+use super::render;
+use crate::{context::Context, parser::Parser};
+
+#[test]
+fn test_render() {
+    let buffer = "a = b
+a & !b
+\t(a | b
+-x
+4
+a ! b
+";
+    let expected: &[&str] = &[
+        "f.txt: invalid token [1:3]: =
+  |
+1 | a = b
+  |   ^ did you mean `=>`?",
+        "f.txt: Parse error [2:5]: got=`!` (Bang): not the beginning of a formula
+  |
+2 | a & !b
+  |     ^ use `~` for negation",
+        "f.txt: Parse error [3:8]: got=`\\n` (Separator): expected `)`
+  |
+3 | \t(a | b
+  | \t      ^ this `(` is never closed",
+        "f.txt: Parse error [4:2]: got=`x` (Identifier): expected `NUMBER`
+  |
+4 | -x
+  |  ^ `-` must be followed by the index of a formula",
+        "f.txt: Index out of bound [5:1]: 4 >= 0 (number of formulas)
+  |
+5 | 4
+  | ^ use `?` to list the formulas",
+        "f.txt: Parse error [6:3]: got=`!` (Bang): expected `SEPARATOR`
+  |
+6 | a ! b
+  |   ^ `!` begins a statement, alone or as `! <method>` with one of `resolution`, \
+`ordered`, `positive`, `negative`, `hyper`, `linear`, `input`; use `~` for negation",
+    ];
+
+    let mut pars = Parser::new();
+    let mut errors = vec![];
+    pars.load_bytes(buffer.to_string());
+    let context = Context::new();
+    for _ in 0..16 {
+        if let Err(err) = pars.parse_statement(&context) {
+            errors.push(err);
+        }
+    }
+    let got: Vec<String> = errors
+        .iter()
+        .map(|err| {
+            let line = err.span().and_then(|s| pars.source_line(s.start().row()));
+            render(err, "f.txt", line)
+        })
+        .collect();
+    for (exp, got) in expected.iter().zip(got.iter()) {
+        if exp != got {
+            panic!("expected=`{exp}`\ngot     =`{got}`")
+        }
+    }
+    assert_eq!(expected.len(), got.len());
+}
@@ -1,63 +1,222 @@
-use crate::token;
-use std::{error::Error, fmt};
+use crate::solver::Method;
+use crate::token::{self, Kind, Span};
+use std::num::ParseIntError;
+use std::{error, fmt, io};
 
-pub type Res<T> = std::result::Result<T, Box<dyn Error>>;
+pub type Res<T> = std::result::Result<T, Error>;
+
+/// kinds that can begin a formula
+pub const FORMULA_START: &[Kind] = &[Kind::Not, Kind::ParenL, Kind::Identifier, Kind::Number];
 
 #[derive(Debug)]
-pub struct InvalidTokenErr {
-    tok: String,
-    row: usize,
-    col: usize,
+pub enum Error {
+    /// characters that do not form any token
+    InvalidToken {
+        literal: String,
+        span: Span,
+    },
+    /// a valid token where it is not allowed
+    UnexpectedToken {
+        found: Kind,
+        literal: String,
+        span: Span,
+        expected: Vec<Kind>,
+    },
+    /// a valid token where a formula should begin
+    ExpectedFormula {
+        found: Kind,
+        literal: String,
+        span: Span,
+    },
+    /// a number too big to be an index
+    InvalidNumber {
+        literal: String,
+        span: Span,
+        source: ParseIntError,
+    },
+    /// an identifier after `!` that is not a method
+    UnknownMethod {
+        literal: String,
+        span: Span,
+    },
+    /// an atom given twice in the ordering after `! ordered`
+    RepeatedAtom {
+        literal: String,
+        span: Span,
+    },
+    /// an index that does not refer to a stored formula
+    IndexOutOfBound {
+        index: usize,
+        len: usize,
+        span: Option<Span>,
+    },
+    /// invalid command-line arguments
+    Usage(String),
+    Io(io::Error),
 }
-impl Error for InvalidTokenErr {}
-impl InvalidTokenErr {
-    pub fn new(tok: String, row: usize, col: usize) -> Box<InvalidTokenErr> {
-        Box::new(InvalidTokenErr { tok, col, row })
+
+impl Error {
+    pub fn invalid_token(literal: &str, span: Span) -> Error {
+        Error::InvalidToken {
+            literal: literal.to_string(),
+            span,
+        }
     }
-}
-impl fmt::Display for InvalidTokenErr {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "invalid token [{}:{}]: {}", self.row, self.col, self.tok)
+    /// if `tok` is an `Invalid` token the error is about the token itself
+    pub fn unexpected(tok: &token::Token, expected: &[Kind]) -> Error {
+        if tok.kind() == Kind::Invalid {
+            return Error::invalid_token(&tok.literal(), tok.span());
+        }
+        Error::UnexpectedToken {
+            found: tok.kind(),
+            literal: tok.literal().to_string(),
+            span: tok.span(),
+            expected: expected.to_vec(),
+        }
+    }
+    /// if `tok` is an `Invalid` token the error is about the token itself
+    pub fn expected_formula(tok: &token::Token) -> Error {
+        if tok.kind() == Kind::Invalid {
+            return Error::invalid_token(&tok.literal(), tok.span());
+        }
+        Error::ExpectedFormula {
+            found: tok.kind(),
+            literal: tok.literal().to_string(),
+            span: tok.span(),
+        }
+    }
+    pub fn invalid_number(tok: &token::Token, source: ParseIntError) -> Error {
+        Error::InvalidNumber {
+            literal: tok.literal().to_string(),
+            span: tok.span(),
+            source,
+        }
+    }
+    pub fn unknown_method(tok: &token::Token) -> Error {
+        Error::UnknownMethod {
+            literal: tok.literal().to_string(),
+            span: tok.span(),
+        }
+    }
+    pub fn repeated_atom(tok: &token::Token) -> Error {
+        Error::RepeatedAtom {
+            literal: tok.literal().to_string(),
+            span: tok.span(),
+        }
+    }
+    pub fn index_out_of_bound(index: usize, len: usize, span: Option<Span>) -> Error {
+        Error::IndexOutOfBound { index, len, span }
     }
-}
 
-#[derive(Debug)]
-pub struct ParseErr {
-    tok: token::Token,
-    message: String,
-}
-impl Error for ParseErr {}
-impl ParseErr {
-    pub fn new(tok: token::Token, message: String) -> Box<ParseErr> {
-        Box::new(ParseErr { tok, message })
+    /// where the error is in the source, if it comes from the source
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::InvalidToken { span, .. }
+            | Error::UnexpectedToken { span, .. }
+            | Error::ExpectedFormula { span, .. }
+            | Error::InvalidNumber { span, .. }
+            | Error::UnknownMethod { span, .. }
+            | Error::RepeatedAtom { span, .. } => Some(*span),
+            Error::IndexOutOfBound { span, .. } => *span,
+            Error::Usage(_) | Error::Io(_) => None,
+        }
     }
-}
-impl fmt::Display for ParseErr {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Parse error [{}:{}]: got=`{}` ({:?}): {}",
-            self.tok.row(),
-            self.tok.col(),
-            self.tok.literal(),
-            self.tok.kind(),
-            self.message
-        )
+
+    /// the kinds that would have been accepted instead of the one found
+    pub fn expected(&self) -> &[Kind] {
+        match self {
+            Error::UnexpectedToken { expected, .. } => expected,
+            Error::ExpectedFormula { .. } => FORMULA_START,
+            _ => &[],
+        }
     }
 }
 
-#[derive(Debug)]
-pub struct IndexOutOfBound {
-    message: String,
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::InvalidNumber { source, .. } => Some(source),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
 }
-impl Error for IndexOutOfBound {}
-impl IndexOutOfBound {
-    pub fn new(message: String) -> Box<IndexOutOfBound> {
-        Box::new(IndexOutOfBound { message })
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
     }
 }
-impl fmt::Display for IndexOutOfBound {
+
+impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Index out of bound: {}", self.message)
+        match self {
+            Error::InvalidToken { literal, span } => {
+                write!(f, "invalid token [{}]: {}", span.start(), literal)
+            }
+            Error::UnexpectedToken {
+                found,
+                literal,
+                span,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "Parse error [{}]: got=`{}` ({found:?}): expected ",
+                    span.start(),
+                    literal.escape_debug()
+                )?;
+                for (i, kind) in expected.iter().enumerate() {
+                    match i {
+                        0 => write!(f, "`{kind}`")?,
+                        _ if i + 1 == expected.len() => write!(f, " or `{kind}`")?,
+                        _ => write!(f, ", `{kind}`")?,
+                    }
+                }
+                Ok(())
+            }
+            Error::ExpectedFormula {
+                found,
+                literal,
+                span,
+            } => write!(
+                f,
+                "Parse error [{}]: got=`{}` ({found:?}): not the beginning of a formula",
+                span.start(),
+                literal.escape_debug()
+            ),
+            Error::InvalidNumber {
+                literal,
+                span,
+                source,
+            } => write!(
+                f,
+                "Parse error [{}]: got=`{literal}` ({:?}): {source}",
+                span.start(),
+                Kind::Number
+            ),
+            Error::UnknownMethod { literal, span } => write!(
+                f,
+                "Parse error [{}]: got=`{literal}` ({:?}): unknown method (expected one of {})",
+                span.start(),
+                Kind::Identifier,
+                Method::names()
+            ),
+            Error::RepeatedAtom { literal, span } => write!(
+                f,
+                "Parse error [{}]: got=`{literal}` ({:?}): repeated in the ordering",
+                span.start(),
+                Kind::Identifier
+            ),
+            Error::IndexOutOfBound { index, len, span } => {
+                write!(f, "Index out of bound")?;
+                if let Some(span) = span {
+                    write!(f, " [{}]", span.start())?;
+                }
+                write!(f, ": {index} >= {len} (number of formulas)")
+            }
+            Error::Usage(message) => write!(f, "Usage error: {message}"),
+            Error::Io(err) => write!(f, "{err}"),
+        }
     }
 }
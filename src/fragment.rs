@@ -0,0 +1,310 @@
+use crate::clause::{ClauseId, Lit, SetClauses};
+use crate::model::Model;
+use crate::symbol::Symbol;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// a class of clause sets with a linear-time algorithm
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Fragment {
+    /// at most one positive literal in each clause
+    Horn,
+    /// at most two literals in each clause
+    TwoCnf,
+    General,
+}
+
+impl Fragment {
+    /// the fragment of the clauses not eliminated, Horn if it is both
+    pub fn of(set: &SetClauses) -> Fragment {
+        let clauses = || set.active().map(|id| set.clause(id));
+        if clauses().all(|c| c.iter().filter(|x| !x.is_negative()).count() <= 1) {
+            Fragment::Horn
+        } else if clauses().all(|c| c.len() <= 2) {
+            Fragment::TwoCnf
+        } else {
+            Fragment::General
+        }
+    }
+    pub fn as_str(&self) -> &str {
+        match self {
+            Fragment::Horn => "Horn",
+            Fragment::TwoCnf => "2-CNF",
+            Fragment::General => "general",
+        }
+    }
+
+    /// None if the fragment has no special algorithm; otherwise a model, or
+    /// None inside if the box is found (it is added to `set` with its proof)
+    pub fn solve(&self, set: &mut SetClauses) -> Option<Option<Model>> {
+        match self {
+            Fragment::Horn => Some(horn(set)),
+            Fragment::TwoCnf => Some(two_sat(set)),
+            Fragment::General => None,
+        }
+    }
+}
+
+impl fmt::Display for Fragment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// the symbols of the clauses not eliminated, sorted by name, and their index
+fn symbols(set: &SetClauses) -> (Vec<Symbol>, HashMap<Symbol, usize>) {
+    let mut symbols: Vec<Symbol> = set
+        .active()
+        .flat_map(|id| set.clause(id).iter().map(|x| x.symbol()))
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    symbols.sort();
+    let index = symbols.iter().enumerate().map(|(i, &s)| (s, i)).collect();
+    (symbols, index)
+}
+
+/// resolves `clause` with each clause of `units`, in order
+fn resolve_all(set: &mut SetClauses, clause: ClauseId, units: &[ClauseId]) -> ClauseId {
+    units.iter().fold(clause, |c, &unit| {
+        set.insert_resolvent(c, unit)
+            .expect("the literal is in the clause")
+    })
+}
+
+/// the marking algorithm for Horn clauses: a symbol is marked true when all
+/// the other symbols of a clause where it is positive are true. Each clause is
+/// visited once for each of its negative literals
+pub fn horn(set: &mut SetClauses) -> Option<Model> {
+    if set.contains(&[]) {
+        return None;
+    }
+    let (symbols, index) = symbols(set);
+    let clauses: Vec<ClauseId> = set.active().collect();
+    // for each clause the negative literals whose symbol is not marked yet
+    let mut missing: Vec<usize> = Vec::with_capacity(clauses.len());
+    let mut negative_in: Vec<Vec<usize>> = vec![Vec::new(); symbols.len()];
+    let mut reason: Vec<Option<ClauseId>> = vec![None; symbols.len()];
+    let mut marked: Vec<usize> = Vec::new();
+    let mut goal = None;
+    for (i, &id) in clauses.iter().enumerate() {
+        let negative: Vec<usize> = set
+            .clause(id)
+            .iter()
+            .filter(|x| x.is_negative())
+            .map(|x| index[&x.symbol()])
+            .collect();
+        for &s in &negative {
+            negative_in[s].push(i);
+        }
+        missing.push(negative.len());
+    }
+    let fire = |i: usize, reason: &mut Vec<Option<ClauseId>>, marked: &mut Vec<usize>| {
+        let id = clauses[i];
+        match set.clause(id).iter().find(|x| !x.is_negative()) {
+            Some(x) => {
+                let s = index[&x.symbol()];
+                if reason[s].is_none() {
+                    reason[s] = Some(id);
+                    marked.push(s);
+                }
+                false
+            }
+            None => true,
+        }
+    };
+    for i in 0..clauses.len() {
+        if missing[i] == 0 && fire(i, &mut reason, &mut marked) {
+            goal = Some(clauses[i]);
+        }
+    }
+    let mut next = 0;
+    while goal.is_none() && next < marked.len() {
+        let s = marked[next];
+        next += 1;
+        for &i in &negative_in[s] {
+            missing[i] -= 1;
+            if missing[i] == 0 && fire(i, &mut reason, &mut marked) {
+                goal = Some(clauses[i]);
+                break;
+            }
+        }
+    }
+    let Some(goal) = goal else {
+        let mut model = Model::new();
+        for (s, &symbol) in symbols.iter().enumerate() {
+            model.set(symbol, reason[s].is_some());
+        }
+        return Some(model);
+    };
+
+    // the proof: only the symbols needed by the goal, each one derived from
+    // the symbols marked before it
+    let negative_symbols = |set: &SetClauses, id: ClauseId| -> Vec<usize> {
+        set.clause(id)
+            .iter()
+            .filter(|x| x.is_negative())
+            .map(|x| index[&x.symbol()])
+            .collect()
+    };
+    let mut needed = vec![false; symbols.len()];
+    let mut stack = negative_symbols(set, goal);
+    while let Some(s) = stack.pop() {
+        if !needed[s] {
+            needed[s] = true;
+            stack.extend(negative_symbols(set, reason[s].expect("it is marked")));
+        }
+    }
+    let mut unit: Vec<Option<ClauseId>> = vec![None; symbols.len()];
+    for &s in marked.iter().filter(|&&s| needed[s]) {
+        let reason = reason[s].expect("it is marked");
+        let units: Vec<ClauseId> = negative_symbols(set, reason)
+            .into_iter()
+            .map(|r| unit[r].expect("marked before"))
+            .collect();
+        unit[s] = Some(resolve_all(set, reason, &units));
+    }
+    let units: Vec<ClauseId> = negative_symbols(set, goal)
+        .into_iter()
+        .map(|s| unit[s].expect("marked"))
+        .collect();
+    resolve_all(set, goal, &units);
+    None
+}
+
+/// 2-SAT with the implication graph: the clause `{a, b}` gives the edges
+/// `~a -> b` and `~b -> a`, the clause `{a}` the edge `~a -> a`. The box is
+/// found if a literal and its opposite are in the same strongly connected
+/// component, otherwise the components give a model
+pub fn two_sat(set: &mut SetClauses) -> Option<Model> {
+    if set.contains(&[]) {
+        return None;
+    }
+    let (symbols, index) = symbols(set);
+    let node = |lit: Lit| 2 * index[&lit.symbol()] + lit.is_negative() as usize;
+    // for each node the edges: (target, clause)
+    let mut edges: Vec<Vec<(usize, ClauseId)>> = vec![Vec::new(); 2 * symbols.len()];
+    for id in set.active() {
+        match *set.clause(id) {
+            [a] => edges[node(a.opposite())].push((node(a), id)),
+            [a, b] => {
+                edges[node(a.opposite())].push((node(b), id));
+                edges[node(b.opposite())].push((node(a), id));
+            }
+            _ => unreachable!("not a 2-CNF clause"),
+        }
+    }
+    let component = tarjan(&edges);
+    let conflict = (0..symbols.len()).find(|&s| component[2 * s] == component[2 * s + 1]);
+    let Some(s) = conflict else {
+        let mut model = Model::new();
+        for (s, &symbol) in symbols.iter().enumerate() {
+            // the components are numbered in reverse topological order
+            model.set(symbol, component[2 * s] < component[2 * s + 1]);
+        }
+        return Some(model);
+    };
+    // x -> ... -> ~x gives {~x}, and ~x -> ... -> x gives {x}
+    let negative = chain(set, &edges, 2 * s, 2 * s + 1);
+    let positive = chain(set, &edges, 2 * s + 1, 2 * s);
+    set.insert_resolvent(negative, positive)
+        .expect("they are opposite");
+    None
+}
+
+/// the strongly connected component of each node (Tarjan, without recursion)
+fn tarjan(edges: &[Vec<(usize, ClauseId)>]) -> Vec<usize> {
+    let n = edges.len();
+    let mut order = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut component = vec![usize::MAX; n];
+    let mut stack = Vec::new();
+    let mut visited = 0;
+    let mut components = 0;
+    for root in 0..n {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        // (node, next edge to visit)
+        let mut calls = vec![(root, 0)];
+        order[root] = visited;
+        low[root] = visited;
+        visited += 1;
+        stack.push(root);
+        while let Some(&mut (v, ref mut next)) = calls.last_mut() {
+            if let Some(&(w, _)) = edges[v].get(*next) {
+                *next += 1;
+                if order[w] == usize::MAX {
+                    order[w] = visited;
+                    low[w] = visited;
+                    visited += 1;
+                    stack.push(w);
+                    calls.push((w, 0));
+                } else if component[w] == usize::MAX {
+                    low[v] = low[v].min(order[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == order[v] {
+                while let Some(w) = stack.pop() {
+                    component[w] = components;
+                    if w == v {
+                        break;
+                    }
+                }
+                components += 1;
+            }
+        }
+    }
+    component
+}
+
+/// resolves the clauses of the shortest path from `from` to `to` (its
+/// opposite), returns the id of the unit clause with `to`
+fn chain(
+    set: &mut SetClauses,
+    edges: &[Vec<(usize, ClauseId)>],
+    from: usize,
+    to: usize,
+) -> ClauseId {
+    let mut parent: Vec<Option<(usize, ClauseId)>> = vec![None; edges.len()];
+    let mut queue = VecDeque::from([from]);
+    while let Some(v) = queue.pop_front() {
+        if v == to {
+            break;
+        }
+        for &(w, id) in &edges[v] {
+            if w != from && parent[w].is_none() {
+                parent[w] = Some((v, id));
+                queue.push_back(w);
+            }
+        }
+    }
+    let mut path = Vec::new();
+    let mut v = to;
+    while v != from {
+        let (u, id) = parent[v].expect("they are in the same component");
+        path.push(id);
+        v = u;
+    }
+    path.reverse();
+    // the resolvent so far is `{to, l}`, where `l` is the last node reached,
+    // or just `{to}` if the last clause was a unit clause
+    let mut clause = path[0];
+    for &id in &path[1..] {
+        if set.clause(clause).len() == 1 {
+            break;
+        }
+        clause = set
+            .insert_resolvent(clause, id)
+            .expect("it follows the path");
+    }
+    clause
+}
@@ -0,0 +1,100 @@
+use super::{horn, two_sat, Fragment};
+use crate::clause::SetClauses;
+use crate::testutil::{set, Random};
+
+fn satisfies_all(set: &SetClauses, model: &crate::model::Model) -> bool {
+    set.ids().all(|id| model.satisfies(set.clause(id)))
+}
+
+#[test]
+fn test_fragment() {
+    assert_eq!(Fragment::of(&set("a ~b ~c, ~a, b")), Fragment::Horn);
+    assert_eq!(Fragment::of(&set("a b, ~a c")), Fragment::TwoCnf);
+    assert_eq!(Fragment::of(&set("a b c, ~a")), Fragment::General);
+    assert_eq!(Fragment::of(&SetClauses::default()), Fragment::Horn);
+    assert_eq!(Fragment::TwoCnf.to_string(), "2-CNF");
+    assert!(Fragment::General.solve(&mut set("a b c")).is_none());
+}
+
+#[test]
+fn test_horn() {
+    let input = set("a, ~a b, ~b ~c d, c, ~d e, ~e ~f");
+    let mut s = input.clone();
+    let model = horn(&mut s).unwrap();
+    assert_eq!(model.to_string(), "{a, b, c, d, e, ~f}");
+    assert!(satisfies_all(&input, &model));
+
+    let mut s = set("a, ~a b, ~b ~c d, c, ~d ~a, ~x y");
+    assert!(horn(&mut s).is_none());
+    assert_eq!(
+        s.trace_from_box(),
+        [
+            "{a}, {b, ~a} -> {b}",
+            "{b}, {d, ~b, ~c} -> {d, ~c}",
+            "{c}, {d, ~c} -> {d}",
+            "{a}, {~a, ~d} -> {~d}",
+            "{d}, {~d} -> {}",
+        ]
+    );
+}
+
+#[test]
+fn test_two_sat() {
+    let input = set("a b, ~a c, ~c ~b, b d");
+    let mut s = input.clone();
+    let model = two_sat(&mut s).unwrap();
+    assert!(satisfies_all(&input, &model));
+
+    let mut s = set("a b, ~a b, a ~b, ~a ~b, c d");
+    assert!(two_sat(&mut s).is_none());
+    assert_eq!(s.trace_from_box().last().unwrap(), "{a}, {~a} -> {}");
+    let mut s = set("x y, ~y z, ~z ~x, ~x y, ~y ~z");
+    assert!(two_sat(&mut s).is_none());
+    assert!(!s.trace_from_box().is_empty());
+}
+
+/// the special algorithms agree with resolution on small random clause sets
+#[test]
+fn test_random() {
+    let mut random = Random::new(7);
+    for round in 0..200 {
+        let horn_round = round % 2 == 0;
+        let mut clauses = Vec::new();
+        for _ in 0..3 + random.below(8) {
+            let len = 1 + random.below(if horn_round { 3 } else { 2 });
+            let mut lits: Vec<String> = Vec::new();
+            for i in 0..len {
+                let var = format!("r{}", random.below(5));
+                // Horn: only the first literal can be positive
+                let negative = if horn_round {
+                    i > 0 || random.below(2) == 0
+                } else {
+                    random.below(2) == 0
+                };
+                lits.push(if negative { format!("~{var}") } else { var });
+            }
+            clauses.push(lits.join(" "));
+        }
+        let input = set(&clauses.join(","));
+        let mut expected = input.clone();
+        let unsat = expected.find_box();
+        let mut s = input.clone();
+        let fragment = Fragment::of(&s);
+        let Some(model) = fragment.solve(&mut s) else {
+            continue;
+        };
+        match model {
+            Some(model) => {
+                assert!(!unsat, "{clauses:?}");
+                assert!(satisfies_all(&input, &model), "{clauses:?}: {model}");
+            }
+            None => {
+                assert!(unsat, "{clauses:?}");
+                assert_eq!(
+                    s.trace_from_box().last().unwrap().split(" -> ").last(),
+                    Some("{}")
+                );
+            }
+        }
+    }
+}
@@ -14,11 +14,27 @@ The precedences of the operators are in decreasing order:
     `!` `&` `|` `=>` `<=>`
 There are some special operators and keywords:
     `!`        -> \"find box\"
+    `! hyper`  -> \"find box with a method: `resolution`, `ordered`,
+                  `positive`, `negative`, `hyper`, `linear` or `input`\"
+    `! ordered c > b > a`
+               -> \"find box with ordered resolution, `c` the greatest atom\"
     `?`        -> \"print formulas currently in use\"
     `-1`       -> \"delete formula_1\"
     `0 <=> ~1` -> \"formula_0 if and only if not formula_1\"
+    `tableau`  -> \"print the tableau of the formulas (or `tableau <formula>`)\"
+    `deduce a` -> \"print a natural deduction proof of `a` from the formulas\"
+    `sequent a, b |- c`
+               -> \"print a sequent calculus derivation, also in LaTeX\"
+    `table 0`  -> \"print the truth table of formula_0 (or `table <formula>`)\"
+    `cnf 0`    -> \"print formula_0 in conjunctive normal form (also `nnf`, `dnf`)\"
+    `minimize 0`
+               -> \"print the minimal sum of products and product of sums of formula_0\"
+    `simplify 0`
+               -> \"print the steps that simplify formula_0\"
     `exit`     -> \"exit the program\"
     `help`     -> \"print this menu\"
-The program can be called followed by an input file.\
+The commands from `tableau` to `simplify` can also be atoms: they are commands
+only at the start of a statement, unless followed by an operator (`table & a`)
+The program can be called followed by input files (see `--help`).\
 "
 }
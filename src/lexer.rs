@@ -1,15 +1,14 @@
-use crate::error::{InvalidTokenErr, Res};
+use crate::symbol::SymbolTable;
 use crate::token;
-use std::collections::HashMap;
-use std::rc::Rc;
 
 #[cfg(test)]
 mod test;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Lexer {
     buffer: String,
-    ids: HashMap<String, Rc<str>>,
+    /// row of the first line of `buffer`
+    first_row: usize,
     pos: usize,
     row: usize,
     col: usize,
@@ -19,7 +18,7 @@ impl Lexer {
     pub fn new() -> Lexer {
         Lexer {
             buffer: "".to_string(),
-            ids: HashMap::new(),
+            first_row: 0,
             pos: 0,
             row: 0,
             col: 0,
@@ -30,6 +29,17 @@ impl Lexer {
         self.pos = 0;
         self.row += 1;
         self.col = 1;
+        self.first_row = self.row;
+    }
+    /// the next buffer loaded starts again from row 1
+    pub fn reset_position(&mut self) {
+        self.row = 0;
+        self.col = 0;
+    }
+    /// the line of the current buffer at `row`, if any
+    pub fn source_line(&self, row: usize) -> Option<&str> {
+        let n = row.checked_sub(self.first_row)?;
+        self.buffer.split(['\n', '\x0C', '\r']).nth(n)
     }
     fn ch(&self) -> Option<u8> {
         if self.pos < self.buffer.len() {
@@ -51,8 +61,10 @@ impl Lexer {
         self.ch()
     }
 
-    /// self.pos -> first unread char
-    pub fn next_tok(&mut self) -> Res<token::Token> {
+    /// self.pos -> first unread char; characters that are not a token become an
+    /// `Invalid` token: the error is reported only when the parser gets there
+    /// (see `Error::unexpected()`)
+    pub fn next_tok(&mut self) -> token::Token {
         self.skip_while(is_space);
         let (init_pos, init_col, init_row) = (self.pos, self.col, self.row);
         let tok_kind = match self.ch() {
@@ -61,7 +73,15 @@ impl Lexer {
             Some(b'(') => token::Kind::ParenL,
             Some(b')') => token::Kind::ParenR,
             Some(b'&') => token::Kind::And,
-            Some(b'|') => token::Kind::Or,
+            Some(b'|') => match self.buffer.as_bytes().get(self.pos + 1) {
+                Some(b'-') => {
+                    self.skip_ch();
+                    token::Kind::Turnstile
+                }
+                _ => token::Kind::Or,
+            },
+            Some(b',') => token::Kind::Comma,
+            Some(b'>') => token::Kind::Greater,
             Some(b'~') => token::Kind::Not,
             Some(b'!') => token::Kind::Bang,
             Some(b'?') => token::Kind::Question,
@@ -112,26 +132,17 @@ impl Lexer {
 
         let s = &self.buffer[init_pos..self.pos];
 
-        if tok_kind == token::Kind::Invalid {
-            Err(InvalidTokenErr::new(s.to_string(), init_row, init_col))
-        } else {
-            // ! if I crate another instance of the string the comparison
-            // beetween atoms does not work anymore
-            Ok(if let Some(rc) = self.ids.get(s) {
-                token::Token::new(tok_kind, Rc::clone(rc), init_row, init_col)
-            } else {
-                let rc = s.into();
-                match s {
-                    // up to now there are only 2 keyword, so I don't worry that much
-                    // an HashMap would be a good alternative
-                    "exit" => token::Token::new(token::Kind::Exit, rc, init_row, init_col),
-                    "help" => token::Token::new(token::Kind::Help, rc, init_row, init_col),
-                    _ => {
-                        self.ids.insert(s.to_string(), Rc::clone(&rc));
-                        token::Token::new(tok_kind, rc, init_row, init_col)
-                    }
-                }
-            })
+        match (tok_kind, s) {
+            (token::Kind::Identifier, _) if token::Kind::keyword(s).is_some() => {
+                let kind = token::Kind::keyword(s).expect("it is a keyword");
+                token::Token::new(kind, s.into(), init_row, init_col)
+            }
+            // identifiers share the name stored in the symbol table
+            (token::Kind::Identifier, _) => {
+                let name = SymbolTable::global().intern(s).name();
+                token::Token::new(tok_kind, name, init_row, init_col)
+            }
+            _ => token::Token::new(tok_kind, s.into(), init_row, init_col),
         }
     }
 
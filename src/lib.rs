@@ -2,20 +2,60 @@ use core::fmt;
 
 pub mod ast;
 pub mod clause;
+pub mod cli;
 pub mod context;
+pub mod deduction;
+pub mod diagnostic;
 pub mod error;
+pub mod fragment;
 pub mod help;
 pub mod lexer;
+pub mod linear;
+pub mod minimize;
+pub mod model;
+pub mod order;
 pub mod parser;
+pub mod preprocess;
 pub mod repl;
+pub mod sequent;
+pub mod simplify;
+pub mod solver;
+pub mod symbol;
+pub mod table;
+pub mod tableau;
+#[cfg(test)]
+mod testutil;
 pub mod token;
 
 pub fn slice_to_str(v: &[impl fmt::Display]) -> String {
-    if v.len() == 0 {
+    if v.is_empty() {
         return "".to_string();
     }
     let first = format!("{}", v.first().unwrap());
-    v.iter()
-        .skip(1)
-        .fold(first, |acc, s| format!("{acc}\n{s}"))
+    v.iter().skip(1).fold(first, |acc, s| format!("{acc}\n{s}"))
+}
+
+/// quotes and escapes `s` as a json string
+pub fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// json array of the (quoted) string representations of `v`
+pub fn json_list(v: &[impl fmt::Display]) -> String {
+    let items: Vec<String> = v.iter().map(|x| json_str(&x.to_string())).collect();
+    format!("[{}]", items.join(","))
 }
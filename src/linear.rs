@@ -0,0 +1,128 @@
+use crate::clause::{self, Clause, ClauseId, SetClauses};
+
+#[cfg(test)]
+mod test;
+
+/// the most centers of a chain: a longer refutation is not looked for
+pub const LIMIT: usize = 10;
+
+/// the most resolvents computed by a search: the chains up to `LIMIT` centers
+/// can be too many, e.g. for clauses with a model
+pub const STEPS: usize = 200_000;
+
+/// the clause a center is resolved with
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    /// the clause at this index of the input clauses
+    Input(usize),
+    /// the center at this index of the chain
+    Ancestor(usize),
+}
+
+/// a chain of resolvents, from an input clause
+struct Chain<'a> {
+    inputs: &'a [Clause],
+    /// the first one is an input clause, each other one is the resolvent of
+    /// the previous one with its side
+    centers: Vec<Clause>,
+    sides: Vec<Side>,
+    /// true if a chain was not extended because of the limit or of the steps
+    cut: bool,
+    /// the resolvents that can still be computed, see `STEPS`
+    steps: usize,
+}
+
+impl Chain<'_> {
+    fn side(&self, side: Side) -> &Clause {
+        match side {
+            Side::Input(k) => &self.inputs[k],
+            Side::Ancestor(a) => &self.centers[a],
+        }
+    }
+
+    /// extends the chain up to `limit` centers, returns true if the box is found
+    fn search(&mut self, limit: usize) -> bool {
+        if self.centers.len() >= limit {
+            self.cut = true;
+            return false;
+        }
+        let ancestors = (0..self.centers.len() - 1).map(Side::Ancestor);
+        for side in (0..self.inputs.len()).map(Side::Input).chain(ancestors) {
+            if self.steps == 0 {
+                self.cut = true;
+                return false;
+            }
+            self.steps -= 1;
+            let center = self.centers.last().expect("a chain is never empty");
+            let Some(resolvent) = clause::resolve(center.lits(), self.side(side).lits()) else {
+                continue;
+            };
+            let found = resolvent.is_empty();
+            // the chain is not longer than it needs to be
+            if !found && self.centers.iter().any(|c| subsumes(c, &resolvent)) {
+                continue;
+            }
+            self.centers.push(resolvent);
+            self.sides.push(side);
+            if found || self.search(limit) {
+                return true;
+            }
+            self.centers.pop();
+            self.sides.pop();
+        }
+        false
+    }
+}
+
+/// true if every literal of `c1` is in `c2`
+fn subsumes(c1: &Clause, c2: &Clause) -> bool {
+    c1.lits().iter().all(|x| c2.lits().binary_search(x).is_ok())
+}
+
+/// linear resolution: looks for a chain from an input clause (the clauses not
+/// eliminated) to the box, each resolvent (the center) resolved with an input
+/// clause or with a previous center. Each chain tried is one center longer than
+/// the previous ones, until the box is found, no chain could be longer or the
+/// chains have `LIMIT` centers or `STEPS` resolvents have been computed (then
+/// the box could be missed): the chain found is added to `set`, so it is the proof
+pub fn find_chain(set: &mut SetClauses) -> bool {
+    let ids: Vec<ClauseId> = set.active().collect();
+    let inputs: Vec<Clause> = ids
+        .iter()
+        .map(|&id| Clause::from(set.clause(id).to_vec()))
+        .collect();
+    let mut steps = STEPS;
+    for limit in 2..=LIMIT {
+        let mut cut = false;
+        for (top, clause) in inputs.iter().enumerate() {
+            let mut chain = Chain {
+                inputs: &inputs,
+                centers: vec![clause.clone()],
+                sides: Vec::new(),
+                cut: false,
+                steps,
+            };
+            if chain.search(limit) {
+                let mut centers = vec![ids[top]];
+                for side in chain.sides {
+                    let side = match side {
+                        Side::Input(k) => ids[k],
+                        Side::Ancestor(a) => centers[a],
+                    };
+                    let center = *centers.last().expect("a chain is never empty");
+                    let resolvent = set
+                        .insert_resolvent(center, side)
+                        .expect("the chain has been resolved");
+                    centers.push(resolvent);
+                }
+                return true;
+            }
+            cut |= chain.cut;
+            steps = chain.steps;
+        }
+        if !cut {
+            return false;
+        }
+    }
+    false
+}
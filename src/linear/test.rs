@@ -0,0 +1,106 @@
+use super::find_chain;
+use crate::clause::Restriction;
+use crate::fragment::Fragment;
+use crate::solver::Solver;
+use crate::testutil::{random_set, set, Random};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_linear() {
+    // not Horn: a center must be resolved with one of its ancestors
+    let mut clauses = set("a b, a ~b, ~a b, ~a ~b");
+    assert!(find_chain(&mut clauses));
+    assert_eq!(
+        clauses.trace_from_box(),
+        [
+            "{a, ~b}, {a, b} -> {a}",
+            "{a}, {b, ~a} -> {b}",
+            "{b}, {~a, ~b} -> {~a}",
+            "{a}, {~a} -> {}"
+        ]
+    );
+    let mut clauses = set("a b, a ~b, ~a b");
+    assert!(!find_chain(&mut clauses));
+
+    // every clause of 3 atoms: the shortest chain has 11 centers, more than `LIMIT`
+    let all = [
+        "a b c", "a b ~c", "a ~b c", "a ~b ~c", "~a b c", "~a b ~c", "~a ~b c", "~a ~b ~c",
+    ];
+    let mut solver = Solver::new();
+    solver.set_restriction(Restriction::Linear);
+    solver.add(&set(&all.join(",")));
+    assert!(!solver.solve());
+    assert!(!solver.is_complete());
+}
+
+#[test]
+fn test_linear_satisfiable() {
+    // every chain up to `LIMIT` centers would take minutes
+    let clauses = [
+        "a b c", "~a d e", "~b ~d f", "c ~e g", "~c h i", "~f ~g j", "d ~h ~j", "e f ~i",
+        "~a ~e h", "b ~f ~h", "g i ~d", "~g j a", "~i ~j c", "h ~b e", "f g ~c", "j ~a f",
+    ];
+    let start = Instant::now();
+    let mut solver = Solver::new();
+    solver.set_restriction(Restriction::Linear);
+    solver.add(&set(&clauses.join(",")));
+    assert!(!solver.solve());
+    assert!(solver.model().is_some());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_input() {
+    let clauses = set("a b, a ~b, ~a b, ~a ~b");
+    assert!(!Restriction::Input.is_complete_for(&clauses));
+    assert!(!clauses.clone().find_box_with(&Restriction::Input));
+    assert!(clauses.clone().find_box_with(&Restriction::Linear));
+
+    // the box is missed only without a model
+    let mut solver = Solver::new();
+    solver.set_restriction(Restriction::Input);
+    solver.add(&clauses);
+    assert!(!solver.solve());
+    assert!(!solver.is_complete());
+    assert!(solver.model().is_none());
+    let mut solver = Solver::new();
+    solver.set_restriction(Restriction::Input);
+    solver.add(&set("a b, a ~b, ~a b"));
+    assert!(!solver.solve());
+    assert!(solver.is_complete());
+    assert!(solver.model().is_some());
+
+    // each step uses an input clause
+    let mut clauses = set("a, ~a b, ~b c, ~c ~a");
+    assert_eq!(Fragment::of(&clauses), Fragment::Horn);
+    assert!(Restriction::Input.is_complete_for(&clauses));
+    assert!(clauses.find_box_with(&Restriction::Input));
+    for id in clauses.ids() {
+        if let Some((c1, c2)) = clauses.parents(id) {
+            assert!(clauses.parents(c1).is_none() || clauses.parents(c2).is_none());
+        }
+    }
+}
+
+/// linear resolution finds the box exactly when resolution does, and so does
+/// input resolution for Horn clauses
+#[test]
+fn test_linear_random() {
+    let mut random = Random::new(23);
+    for _ in 0..200 {
+        let input = random_set(&mut random, "l");
+        let unsat = input.clone().find_box();
+        assert_eq!(
+            input.clone().find_box_with(&Restriction::Linear),
+            unsat,
+            "{input}"
+        );
+        if Restriction::Input.is_complete_for(&input) {
+            assert_eq!(
+                input.clone().find_box_with(&Restriction::Input),
+                unsat,
+                "{input}"
+            );
+        }
+    }
+}
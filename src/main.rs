@@ -1,13 +1,28 @@
+use logic_resolution::cli::{self, Options};
 use logic_resolution::error::Res;
 use logic_resolution::repl;
-use std::env;
+use std::io::{self, IsTerminal};
+use std::{env, process};
 
 fn main() -> Res<()> {
     let args: Vec<String> = env::args().collect();
-    match args.len() {
-        1 => repl::repl()?,
-        2 => repl::rep(&args[1])?,
-        _ => println!("Usage: {} [file]", args[0]),
+    let mut options = match Options::parse(&args[1..]) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", cli::usage(&args[0]));
+            process::exit(cli::EXIT_USAGE);
+        }
+    };
+    if options.help() {
+        println!("{}", cli::usage(&args[0]));
+        process::exit(cli::EXIT_OK);
+    }
+    options.resolve_stdin(io::stdin().is_terminal());
+    if options.interactive() {
+        repl::repl(&options)?;
+    } else {
+        process::exit(repl::batch(&options)?);
     }
     Ok(())
 }
@@ -0,0 +1,255 @@
+use crate::ast::Formula;
+use crate::model::Model;
+use crate::symbol::Symbol;
+use crate::table::{self, TooLarge};
+use crate::token::Kind;
+use std::collections::BTreeSet;
+
+#[cfg(test)]
+mod test;
+
+/// a product of literals over the atoms, the first atom is the most
+/// significant bit: the atoms in `mask` are not in it, the others are true
+/// if their bit in `bits` is set, false otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Implicant {
+    bits: usize,
+    mask: usize,
+}
+
+impl Implicant {
+    /// true if the implicant is true in the assignment `minterm`
+    pub fn covers(&self, minterm: usize) -> bool {
+        minterm & !self.mask == self.bits
+    }
+    /// the number of literals for `n` atoms
+    pub fn size(&self, n: usize) -> usize {
+        n - (self.mask & ((1 << n) - 1)).count_ones() as usize
+    }
+    /// the literals, as the index of the atom and true if negated
+    fn literals(&self, n: usize) -> impl Iterator<Item = (usize, bool)> + '_ {
+        (0..n)
+            .filter(move |i| self.mask >> (n - 1 - i) & 1 == 0)
+            .map(move |i| (i, self.bits >> (n - 1 - i) & 1 == 0))
+    }
+}
+
+/// the prime implicants of the assignments `minterms` (Quine-McCluskey): the
+/// implicants that differ in one atom are merged until none can be
+fn primes(minterms: &[usize]) -> Vec<Implicant> {
+    let mut primes = BTreeSet::new();
+    let mut current: BTreeSet<Implicant> = minterms
+        .iter()
+        .map(|&bits| Implicant { bits, mask: 0 })
+        .collect();
+    while !current.is_empty() {
+        let mut next = BTreeSet::new();
+        let mut merged = BTreeSet::new();
+        for a in &current {
+            for b in current.range(a..).skip(1) {
+                let diff = a.bits ^ b.bits;
+                if a.mask == b.mask && diff.count_ones() == 1 {
+                    next.insert(Implicant {
+                        bits: a.bits & !diff,
+                        mask: a.mask | diff,
+                    });
+                    merged.insert(*a);
+                    merged.insert(*b);
+                }
+            }
+        }
+        primes.extend(current.difference(&merged).copied());
+        current = next;
+    }
+    primes.into_iter().collect()
+}
+
+/// a smallest set of `primes` covering the `minterms`, then with the fewest
+/// literals: the essential ones first, then a branch and bound search
+fn cover(primes: &[Implicant], minterms: &[usize], n: usize) -> Vec<Implicant> {
+    let mut chosen = Vec::new();
+    let mut left: Vec<usize> = minterms.to_vec();
+    loop {
+        let essential = left.iter().find_map(|&m| {
+            let mut covering = primes.iter().filter(|p| p.covers(m));
+            match (covering.next(), covering.next()) {
+                (Some(p), None) => Some(*p),
+                _ => None,
+            }
+        });
+        let Some(p) = essential else { break };
+        chosen.push(p);
+        left.retain(|&m| !p.covers(m));
+    }
+    let mut best = None;
+    search(primes, &left, &mut chosen, &mut best, n);
+    let mut best = best.expect("every minterm is covered by a prime");
+    best.sort();
+    best
+}
+
+/// the cost of a cover: the implicants, then the literals
+fn cost(cover: &[Implicant], n: usize) -> (usize, usize) {
+    (cover.len(), cover.iter().map(|p| p.size(n)).sum())
+}
+
+fn search(
+    primes: &[Implicant],
+    left: &[usize],
+    chosen: &mut Vec<Implicant>,
+    best: &mut Option<Vec<Implicant>>,
+    n: usize,
+) {
+    if let Some(best) = best {
+        // one more implicant is needed if some minterm is left
+        let bound = chosen.len() + usize::from(!left.is_empty());
+        if bound > best.len() || (left.is_empty() && cost(chosen, n) >= cost(best, n)) {
+            return;
+        }
+    }
+    // the minterm covered by the fewest primes
+    let Some(&m) = left
+        .iter()
+        .min_by_key(|&&m| primes.iter().filter(|p| p.covers(m)).count())
+    else {
+        *best = Some(chosen.clone());
+        return;
+    };
+    for p in primes.iter().filter(|p| p.covers(m)) {
+        chosen.push(*p);
+        let rest: Vec<usize> = left.iter().copied().filter(|&m| !p.covers(m)).collect();
+        search(primes, &rest, chosen, best, n);
+        chosen.pop();
+    }
+}
+
+/// the minimal sum of products and product of sums of a formula, found from
+/// its truth table
+#[derive(Debug, Clone)]
+pub struct Minimization {
+    atoms: Vec<Symbol>,
+    /// the prime implicants of the formula
+    primes: Vec<Implicant>,
+    /// the products of the sum, some of the primes
+    sum: Vec<Implicant>,
+    /// the prime implicants of the negation that cover it, each one is the
+    /// negation of a sum of the product
+    product: Vec<Implicant>,
+}
+
+impl Minimization {
+    /// the atoms in the order they appear in `formula`, at most `limit` (and
+    /// at most `table::MAX_LIMIT`)
+    pub fn new(formula: &Formula, limit: usize) -> Result<Minimization, TooLarge> {
+        let limit = limit.min(table::MAX_LIMIT);
+        let atoms = formula.symbols();
+        if atoms.len() > limit {
+            return Err(TooLarge {
+                atoms: atoms.len(),
+                limit,
+            });
+        }
+        let n = atoms.len();
+        let (mut ones, mut zeros) = (Vec::new(), Vec::new());
+        for m in 0..1usize << n {
+            match formula.eval(&assignment(&atoms, m)) {
+                Some(true) => ones.push(m),
+                _ => zeros.push(m),
+            }
+        }
+        let primes = primes(&ones);
+        let sum = cover(&primes, &ones, n);
+        let product = cover(&self::primes(&zeros), &zeros, n);
+        Ok(Minimization {
+            atoms,
+            primes,
+            sum,
+            product,
+        })
+    }
+    pub fn atoms(&self) -> &[Symbol] {
+        &self.atoms
+    }
+    pub fn primes(&self) -> &[Implicant] {
+        &self.primes
+    }
+
+    /// the implicant as a formula, `a | ~a` if it has no literals
+    pub fn implicant(&self, implicant: &Implicant) -> Formula {
+        let literals = implicant.literals(self.atoms.len()).map(|(i, negated)| {
+            let atom = Formula::new_leaf(self.atoms[i]);
+            if negated {
+                Formula::new_unary(Kind::Not, atom)
+            } else {
+                atom
+            }
+        });
+        self.chain(literals.collect(), Kind::And)
+    }
+
+    /// the minimal sum of products
+    pub fn sum_of_products(&self) -> Formula {
+        let products = self.sum.iter().map(|p| self.implicant(p)).collect();
+        self.chain(products, Kind::Or)
+    }
+
+    /// the minimal product of sums, from the sum of products of the negation
+    pub fn product_of_sums(&self) -> Formula {
+        let n = self.atoms.len();
+        let sums = self
+            .product
+            .iter()
+            .map(|p| {
+                let literals = p.literals(n).map(|(i, negated)| {
+                    let atom = Formula::new_leaf(self.atoms[i]);
+                    if negated {
+                        atom
+                    } else {
+                        Formula::new_unary(Kind::Not, atom)
+                    }
+                });
+                self.chain(literals.collect(), Kind::Or)
+            })
+            .collect();
+        self.chain(sums, Kind::And)
+    }
+
+    /// true if `formula` has the same value as both minimal forms in every
+    /// assignment of the atoms
+    pub fn is_equivalent(&self, formula: &Formula) -> bool {
+        let (sum, product) = (self.sum_of_products(), self.product_of_sums());
+        (0..1usize << self.atoms.len()).all(|m| {
+            let model = assignment(&self.atoms, m);
+            let value = formula.eval(&model);
+            value.is_some() && sum.eval(&model) == value && product.eval(&model) == value
+        })
+    }
+
+    /// the formulas joined by `operator` from the left; with no formulas the
+    /// neutral element: `a | ~a` for `&`, `a & ~a` for `|`
+    fn chain(&self, formulas: Vec<Formula>, operator: Kind) -> Formula {
+        let mut formulas = formulas.into_iter();
+        match formulas.next() {
+            Some(first) => formulas.fold(first, |l, r| Formula::new_binary(l, operator, r)),
+            None => {
+                let atom = Formula::new_leaf(self.atoms[0]);
+                let other = match operator {
+                    Kind::And => Kind::Or,
+                    _ => Kind::And,
+                };
+                let not = Formula::new_unary(Kind::Not, atom.clone());
+                Formula::new_binary(atom, other, not)
+            }
+        }
+    }
+}
+
+/// the assignment of the `atoms` numbered `m`, the first atom is the most
+/// significant bit
+fn assignment(atoms: &[Symbol], m: usize) -> Model {
+    let mut model = Model::new();
+    for (i, &atom) in atoms.iter().enumerate() {
+        model.set(atom, m >> (atoms.len() - 1 - i) & 1 == 1);
+    }
+    model
+}
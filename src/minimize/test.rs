@@ -0,0 +1,97 @@
+use super::{primes, Minimization};
+use crate::ast::Formula;
+use crate::symbol::Symbol;
+use crate::table::{TooLarge, MAX_LIMIT};
+use crate::token::Kind;
+
+/// the sum of the `minterms` of the `atoms`, `a & ~a` if there are none
+fn sum_of_minterms(atoms: &[Symbol], minterms: &[usize]) -> Formula {
+    let n = atoms.len();
+    let products = minterms.iter().map(|&m| {
+        let literals = atoms.iter().enumerate().map(|(i, &s)| {
+            let atom = Formula::new_leaf(s);
+            match m >> (n - 1 - i) & 1 {
+                1 => atom,
+                _ => Formula::new_unary(Kind::Not, atom),
+            }
+        });
+        literals
+            .reduce(|l, r| Formula::new_binary(l, Kind::And, r))
+            .unwrap()
+    });
+    let contradiction = Formula::new_binary(
+        Formula::new_leaf(atoms[0]),
+        Kind::And,
+        Formula::new_unary(Kind::Not, Formula::new_leaf(atoms[0])),
+    );
+    products
+        .reduce(|l, r| Formula::new_binary(l, Kind::Or, r))
+        .unwrap_or(contradiction)
+}
+
+#[test]
+fn test_minimize() {
+    let atoms = ["a", "b", "c"].map(Symbol::intern);
+    // a cyclic core: no prime is essential
+    let f = sum_of_minterms(&atoms, &[0, 1, 2, 5, 6, 7]);
+    let minimization = Minimization::new(&f, 3).unwrap();
+    assert_eq!(minimization.primes().len(), 6);
+    assert_eq!(
+        minimization.sum_of_products().to_string(),
+        "((((~a) & (~b)) | (b & (~c))) | (a & c))"
+    );
+    assert_eq!(
+        minimization.product_of_sums().to_string(),
+        "(((a | (~b)) | (~c)) & (((~a) | b) | c))"
+    );
+    assert!(minimization.is_equivalent(&f));
+    assert!(!minimization.is_equivalent(&sum_of_minterms(&atoms, &[0])));
+    assert!(Minimization::new(&f, 2).is_err());
+}
+
+/// every function of 3 atoms: the sum has the fewest primes that cover it
+#[test]
+fn test_minimize_all() {
+    let atoms = ["a", "b", "c"].map(Symbol::intern);
+    for function in 0..256usize {
+        let minterms: Vec<usize> = (0..8).filter(|m| function >> m & 1 == 1).collect();
+        let f = sum_of_minterms(&atoms, &minterms);
+        let minimization = Minimization::new(&f, 3).unwrap();
+        assert!(minimization.is_equivalent(&f), "{f}");
+        assert!(minimization
+            .sum
+            .iter()
+            .all(|p| minimization.primes.contains(p)));
+        let primes = primes(&minterms);
+        let smallest = (0..1usize << primes.len())
+            .filter(|subset| {
+                minterms.iter().all(|&m| {
+                    (0..primes.len()).any(|i| subset >> i & 1 == 1 && primes[i].covers(m))
+                })
+            })
+            .map(|subset| subset.count_ones() as usize)
+            .min()
+            .unwrap();
+        assert_eq!(minimization.sum.len(), smallest, "{f}");
+    }
+}
+
+#[test]
+fn test_minimize_limit() {
+    let atoms: Vec<Formula> = (0..=MAX_LIMIT)
+        .map(|i| Formula::new_leaf(Symbol::intern(&format!("x{i}"))))
+        .collect();
+    let f = atoms
+        .into_iter()
+        .reduce(|l, r| Formula::new_binary(l, Kind::And, r))
+        .unwrap();
+    // a greater limit is `MAX_LIMIT`
+    let err = Minimization::new(&f, usize::MAX).unwrap_err();
+    assert_eq!(
+        err,
+        TooLarge {
+            atoms: MAX_LIMIT + 1,
+            limit: MAX_LIMIT
+        }
+    );
+}
@@ -0,0 +1,122 @@
+use crate::clause::Lit;
+use crate::symbol::Symbol;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// an assignment of the symbols, sorted by name
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Model {
+    values: BTreeMap<Symbol, bool>,
+}
+
+impl Model {
+    pub fn new() -> Model {
+        Model::default()
+    }
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    pub fn value(&self, symbol: Symbol) -> Option<bool> {
+        self.values.get(&symbol).copied()
+    }
+    pub fn set(&mut self, symbol: Symbol, value: bool) {
+        self.values.insert(symbol, value);
+    }
+    /// makes `lit` true
+    pub fn set_lit(&mut self, lit: Lit) {
+        self.set(lit.symbol(), !lit.is_negative());
+    }
+    /// None if the symbol of `lit` is not assigned
+    pub fn lit_value(&self, lit: Lit) -> Option<bool> {
+        self.value(lit.symbol()).map(|v| v != lit.is_negative())
+    }
+    /// true if at least one literal of the clause is true
+    pub fn satisfies(&self, clause: &[Lit]) -> bool {
+        clause.iter().any(|&lit| self.lit_value(lit) == Some(true))
+    }
+    /// a model of the `clauses` assigning all the `symbols`, found by
+    /// backtracking over them in order (false first) with unit propagation;
+    /// None if there is none
+    pub fn search(clauses: &[&[Lit]], symbols: &[Symbol]) -> Option<Model> {
+        let mut model = Model::new();
+        model.extend_search(clauses, symbols).then_some(model)
+    }
+    fn extend_search(&mut self, clauses: &[&[Lit]], symbols: &[Symbol]) -> bool {
+        let mut forced = Vec::new();
+        loop {
+            let mut unit = None;
+            for clause in clauses {
+                if self.satisfies(clause) {
+                    continue;
+                }
+                let mut free = clause.iter().filter(|&&x| self.lit_value(x).is_none());
+                match (free.next(), free.next()) {
+                    (None, _) => {
+                        for s in forced {
+                            self.values.remove(&s);
+                        }
+                        return false;
+                    }
+                    (Some(&x), None) => {
+                        unit = Some(x);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match unit {
+                Some(x) => {
+                    self.set_lit(x);
+                    forced.push(x.symbol());
+                }
+                None => break,
+            }
+        }
+        let Some(&symbol) = symbols.iter().find(|&&s| self.value(s).is_none()) else {
+            return true;
+        };
+        for value in [false, true] {
+            self.set(symbol, value);
+            if self.extend_search(clauses, symbols) {
+                return true;
+            }
+        }
+        self.values.remove(&symbol);
+        for s in forced {
+            self.values.remove(&s);
+        }
+        false
+    }
+    /// the true literals
+    pub fn lits(&self) -> Vec<Lit> {
+        self.values
+            .iter()
+            .map(|(&s, &v)| {
+                if v {
+                    Lit::positive(s)
+                } else {
+                    Lit::negative(s)
+                }
+            })
+            .collect()
+    }
+}
+
+/// the true literals, e.g. `{a, ~b}`
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = self
+            .lits()
+            .iter()
+            .map(|lit| lit.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{{{s}}}")
+    }
+}
@@ -0,0 +1,24 @@
+use super::Model;
+use crate::clause::Lit;
+use crate::symbol::Symbol;
+
+#[test]
+fn test_model() {
+    let (a, b, c) = (
+        Symbol::intern("model_a"),
+        Symbol::intern("model_b"),
+        Symbol::intern("model_c"),
+    );
+    let mut model = Model::new();
+    assert!(model.is_empty());
+    model.set_lit(Lit::negative(b));
+    model.set(a, true);
+    assert_eq!(model.len(), 2);
+    assert_eq!(model.to_string(), "{model_a, ~model_b}");
+    assert_eq!(model.lit_value(Lit::positive(b)), Some(false));
+    assert_eq!(model.lit_value(Lit::negative(b)), Some(true));
+    assert_eq!(model.lit_value(Lit::positive(c)), None);
+    assert!(model.satisfies(&[Lit::positive(b), Lit::positive(a)]));
+    assert!(!model.satisfies(&[Lit::positive(b), Lit::positive(c)]));
+    assert!(!model.satisfies(&[]));
+}
@@ -0,0 +1,165 @@
+use crate::clause::Lit;
+use crate::symbol::Symbol;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod test;
+
+/// which literals of a clause are selected: if a clause has a selected literal
+/// it can be resolved only upon it
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Selection {
+    #[default]
+    None,
+    /// the greatest negative literal
+    Negative,
+}
+
+impl Selection {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Selection::None => "none",
+            Selection::Negative => "negative",
+        }
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Selection, String> {
+        match s {
+            "none" => Ok(Selection::None),
+            "negative" => Ok(Selection::Negative),
+            _ => Err(format!(
+                "unknown selection `{s}` (expected `none` or `negative`)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Selection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// the ordering of ordered resolution: the atoms given are the greatest, from
+/// the first one; the others are smaller, and ordered by name (`a < b`)
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Order {
+    atoms: Vec<Symbol>,
+    selection: Selection,
+}
+
+impl Order {
+    pub fn new(atoms: Vec<Symbol>, selection: Selection) -> Order {
+        Order { atoms, selection }
+    }
+    pub fn atoms(&self) -> &[Symbol] {
+        &self.atoms
+    }
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+    /// the ordering restricted to `symbols`
+    pub fn ranking(&self, symbols: impl Iterator<Item = Symbol>) -> Ranking {
+        let mut symbols: Vec<Symbol> = symbols.collect();
+        symbols.sort();
+        symbols.dedup();
+        let given: HashMap<Symbol, usize> = self
+            .atoms
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| (s, i))
+            .collect();
+        // from the smallest to the greatest
+        symbols.sort_by_key(|s| (given.get(s).map(|&i| usize::MAX - i), *s));
+        Ranking {
+            rank: symbols.iter().enumerate().map(|(i, &s)| (s, i)).collect(),
+            symbols,
+            selection: self.selection,
+        }
+    }
+}
+
+/// the atoms are separated by `>` or `,`, from the greatest: "c > b > a"
+impl FromStr for Order {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Order, String> {
+        let mut atoms = Vec::new();
+        for atom in s.split(['>', ',']).map(str::trim) {
+            let valid = atom.starts_with(|c: char| c.is_ascii_alphabetic())
+                && atom.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(format!("`{atom}` is not an atom"));
+            }
+            let symbol = Symbol::intern(atom);
+            if atoms.contains(&symbol) {
+                return Err(format!("`{atom}` is repeated"));
+            }
+            atoms.push(symbol);
+        }
+        Ok(Order::new(atoms, Selection::default()))
+    }
+}
+
+/// an `Order` for some atoms: the rank of each one
+#[derive(Debug, Clone)]
+pub struct Ranking {
+    /// from the smallest to the greatest
+    symbols: Vec<Symbol>,
+    rank: HashMap<Symbol, usize>,
+    selection: Selection,
+}
+
+impl Ranking {
+    /// panics if the symbol is not ranked
+    pub fn rank(&self, symbol: Symbol) -> usize {
+        self.rank[&symbol]
+    }
+    /// `~p` is greater than `p`, which is greater than the literals of smaller atoms
+    pub fn lit_rank(&self, lit: Lit) -> usize {
+        2 * self.rank(lit.symbol()) + lit.is_negative() as usize
+    }
+    /// the selected literal of `clause`, if any
+    pub fn selected(&self, clause: &[Lit]) -> Option<Lit> {
+        match self.selection {
+            Selection::None => None,
+            Selection::Negative => clause
+                .iter()
+                .copied()
+                .filter(|x| x.is_negative())
+                .max_by_key(|&x| self.lit_rank(x)),
+        }
+    }
+    /// `lit` can be resolved upon if it is selected or, if nothing is
+    /// selected, if its atom is the greatest of the clause
+    pub fn eligible(&self, clause: &[Lit], lit: Lit) -> bool {
+        match self.selected(clause) {
+            Some(selected) => selected == lit,
+            None => clause
+                .iter()
+                .all(|&x| self.rank(x.symbol()) <= self.rank(lit.symbol())),
+        }
+    }
+    /// true if the clauses can be resolved upon their (only) opposite literals
+    pub fn allows(&self, c1: &[Lit], c2: &[Lit]) -> bool {
+        c1.iter()
+            .find(|x| c2.contains(&x.opposite()))
+            .is_some_and(|&x| self.eligible(c1, x) && self.eligible(c2, x.opposite()))
+    }
+}
+
+/// from the greatest: "c > b > a"
+impl fmt::Display for Ranking {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let atoms: Vec<String> = self.symbols.iter().rev().map(|s| s.to_string()).collect();
+        write!(f, "{}", atoms.join(" > "))?;
+        if self.selection != Selection::None {
+            write!(f, " ({} selection)", self.selection)?;
+        }
+        Ok(())
+    }
+}
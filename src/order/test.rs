@@ -0,0 +1,76 @@
+use super::{Order, Selection};
+use crate::clause::{Clause, Lit, Restriction};
+use crate::solver::Solver;
+use crate::symbol::Symbol;
+use crate::testutil::{random_set, Random};
+
+fn lit(name: &str) -> Lit {
+    match name.strip_prefix('~') {
+        Some(name) => Lit::negative(Symbol::intern(name)),
+        None => Lit::positive(Symbol::intern(name)),
+    }
+}
+
+fn clause(lits: &str) -> Vec<Lit> {
+    let mut clause = Clause::new();
+    for x in lits.split_whitespace() {
+        clause.insert(lit(x));
+    }
+    clause.lits().to_vec()
+}
+
+#[test]
+fn test_order() {
+    let order: Order = "c > a".parse().unwrap();
+    assert_eq!(order.atoms(), &[Symbol::intern("c"), Symbol::intern("a")]);
+    assert_eq!("c,a".parse::<Order>().unwrap(), order);
+    assert!("c > > a".parse::<Order>().is_err());
+    assert!("c > 1".parse::<Order>().is_err());
+    assert!("a > c > a".parse::<Order>().is_err());
+    assert_eq!("negative".parse(), Ok(Selection::Negative));
+    assert!("all".parse::<Selection>().is_err());
+
+    let symbols = ["a", "b", "c", "d"].map(Symbol::intern);
+    let ranking = order.ranking(symbols.into_iter());
+    assert_eq!(ranking.to_string(), "c > a > d > b");
+    assert!(ranking.lit_rank(lit("~a")) > ranking.lit_rank(lit("a")));
+    assert!(ranking.eligible(&clause("a ~b"), lit("a")));
+    assert!(!ranking.eligible(&clause("a ~b"), lit("~b")));
+    assert!(!ranking.allows(&clause("a ~b"), &clause("~a c")));
+    assert!(ranking.allows(&clause("a ~b"), &clause("~a d")));
+
+    let ranking = Order::new(Vec::new(), Selection::Negative).ranking(symbols.into_iter());
+    assert_eq!(ranking.to_string(), "d > c > b > a (negative selection)");
+    assert_eq!(ranking.selected(&clause("d ~a ~b")), Some(lit("~b")));
+    assert!(ranking.eligible(&clause("d ~a ~b"), lit("~b")));
+    assert!(!ranking.eligible(&clause("d ~a ~b"), lit("d")));
+    assert!(ranking.eligible(&clause("d a"), lit("d")));
+}
+
+/// ordered resolution finds the box exactly when resolution does, and its
+/// models are models
+#[test]
+fn test_ordered_random() {
+    let mut random = Random::new(11);
+    let orders = ["", "o3 > o0", "o1 > o4 > o2"];
+    for round in 0..150 {
+        let input = random_set(&mut random, "o");
+        let unsat = input.clone().find_box();
+        let mut order: Order = match orders[round % 3] {
+            "" => Order::default(),
+            s => s.parse().unwrap(),
+        };
+        if round % 2 == 0 {
+            order = Order::new(order.atoms().to_vec(), Selection::Negative);
+        }
+        let mut solver = Solver::new();
+        solver.set_restriction(Restriction::Ordered(order));
+        solver.add(&input);
+        assert_eq!(solver.solve(), unsat, "{input}");
+        if let Some(model) = solver.model() {
+            for id in input.ids() {
+                assert!(model.satisfies(input.clause(id)), "{input}: {model}");
+            }
+        }
+    }
+}
@@ -1,12 +1,78 @@
-use std::rc::Rc;
+use std::fmt;
+use std::sync::Arc;
 
+use crate::ast::{Formula, NormalForm};
 use crate::context::Context;
-use crate::error::{ParseErr, Res};
+use crate::error::{Error, Res};
+use crate::sequent::Sequent;
+use crate::solver::Method;
+use crate::symbol::Symbol;
 use crate::{ast, lexer, token};
 
 #[cfg(test)]
 mod test;
 
+pub enum Statement {
+    Formula(Formula),
+    Eoi,
+    Exit,
+    Help,
+    /// `!`, optionally followed by the method, e.g. `! hyper`, and by the atoms
+    /// of the ordering of `ordered`, from the greatest: `! ordered c > b > a`
+    Execute(Option<Method>, Vec<Symbol>),
+    Query,
+    Delete(usize),
+    /// `tableau`, of the formula if there is one, otherwise of all the formulas
+    Tableau(Option<Formula>),
+    /// `deduce`, a natural deduction proof of the formula from all the formulas
+    Deduce(Formula),
+    /// `sequent a, b |- c`, a derivation of the sequent, either side can be empty
+    Sequent(Sequent),
+    /// `table`, the truth table of the formula
+    Table(Formula),
+    /// `nnf`, `cnf` or `dnf`, the formula in the normal form
+    NormalForm(NormalForm, Formula),
+    /// `minimize`, the minimal sum of products and product of sums of the formula
+    Minimize(Formula),
+    /// `simplify`, the formula rewritten step by step
+    Simplify(Formula),
+}
+
+impl From<Formula> for Statement {
+    fn from(f: Formula) -> Statement {
+        Statement::Formula(f)
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Formula(formula) => write!(f, "{formula}"),
+            Statement::Execute(None, _) => write!(f, "EXECUTE"),
+            Statement::Execute(Some(method), atoms) if atoms.is_empty() => {
+                write!(f, "EXECUTE {method}")
+            }
+            Statement::Execute(Some(method), atoms) => {
+                let atoms: Vec<String> = atoms.iter().map(|a| a.to_string()).collect();
+                write!(f, "EXECUTE {method} {}", atoms.join(" > "))
+            }
+            Statement::Query => write!(f, "QUERY"),
+            Statement::Delete(n) => write!(f, "DELETE {n}"),
+            Statement::Eoi => write!(f, "END OF INPUT"),
+            Statement::Exit => write!(f, "EXIT"),
+            Statement::Help => write!(f, "HELP"),
+            Statement::Tableau(None) => write!(f, "TABLEAU"),
+            Statement::Tableau(Some(formula)) => write!(f, "TABLEAU {formula}"),
+            Statement::Deduce(formula) => write!(f, "DEDUCE {formula}"),
+            Statement::Sequent(sequent) => write!(f, "SEQUENT {sequent}"),
+            Statement::Table(formula) => write!(f, "TABLE {formula}"),
+            Statement::NormalForm(form, formula) => write!(f, "{form} {formula}"),
+            Statement::Minimize(formula) => write!(f, "MINIMIZE {formula}"),
+            Statement::Simplify(formula) => write!(f, "SIMPLIFY {formula}"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Parser {
     lex: lexer::Lexer,
@@ -14,28 +80,41 @@ pub struct Parser {
     peek_tok: Option<token::Token>,
 }
 
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
+
 impl Parser {
-    pub fn new() -> Res<Parser> {
+    pub fn new() -> Parser {
         let mut p = Parser {
             lex: lexer::Lexer::new(),
             curr_tok: None,
             peek_tok: None,
         };
-        p.init()?;
-        Ok(p)
+        p.init();
+        p
     }
 
-    fn init(&mut self) -> Res<()> {
-        for _ in 0..2 {
-            self.curr_tok = self.peek_tok.take();
-            self.peek_tok = Some(self.lex.next_tok()?);
-        }
-        Ok(())
+    fn init(&mut self) {
+        self.curr_tok = Some(self.lex.next_tok());
+        self.peek_tok = Some(self.lex.next_tok());
     }
 
-    pub fn load_bytes(&mut self, buffer: String) -> Res<()> {
+    pub fn load_bytes(&mut self, buffer: String) {
         self.lex.load_bytes(buffer);
-        self.init()
+        self.init();
+    }
+
+    /// the next buffer loaded starts again from row 1 (e.g. a new file)
+    pub fn reset_position(&mut self) {
+        self.lex.reset_position();
+    }
+
+    /// the line at `row` of the last buffer loaded, used to show errors
+    pub fn source_line(&self, row: usize) -> Option<&str> {
+        self.lex.source_line(row)
     }
 
     fn curr_tok(&self) -> &token::Token {
@@ -44,26 +123,32 @@ impl Parser {
             .expect("None should be only the intial value")
     }
 
+    fn peek_tok(&self) -> &token::Token {
+        self.peek_tok
+            .as_ref()
+            .expect("None should be only the intial value")
+    }
+
     /// returns the previous token
-    fn skip_tok(&mut self) -> Res<token::Token> {
+    fn skip_tok(&mut self) -> token::Token {
         let tok = self
             .curr_tok
             .take()
             .expect("None should be only the intial value");
         self.curr_tok = self.peek_tok.take();
-        self.peek_tok = Some(self.lex.next_tok()?);
-        Ok(tok)
+        self.peek_tok = Some(self.lex.next_tok());
+        tok
     }
 
-    pub fn parse_statement_update_context(&mut self, context: &mut Context) -> Res<ast::Statement> {
+    pub fn parse_statement_update_context(&mut self, context: &mut Context) -> Res<Statement> {
         let retval = self.parse_statement(context)?;
         match retval {
-            ast::Statement::Delete(n) => {
+            Statement::Delete(n) => {
                 context.remove(n)?;
                 Ok(retval)
             }
-            ast::Statement::Formula(f) => {
-                context.push(Rc::new(f.clone()))?; // it uses Rc::clone() inside
+            Statement::Formula(f) => {
+                context.push(Arc::new(f.clone()))?; // it uses Arc::clone() inside
                 Ok(f.into())
             }
             _ => Ok(retval),
@@ -71,32 +156,85 @@ impl Parser {
     }
 
     /// does NOT auto-update the context
-    /// skips only the first token if it is invalid
-    /// does not skip what is there instead of ``
-    pub fn parse_statement(&mut self, context: &Context) -> Res<ast::Statement> {
+    /// after an error the rest of the statement is skipped (up to the next separator)
+    pub fn parse_statement(&mut self, context: &Context) -> Res<Statement> {
+        let retval = self.parse_statement_inner(context);
+        if retval.is_err() {
+            self.synchronize()?;
+        }
+        retval
+    }
+
+    /// panic-mode recovery: skips everything up to the next separator, which is
+    /// left to be skipped by the next statement
+    fn synchronize(&mut self) -> Res<()> {
+        while !self.curr_tok().kind().is_sep() {
+            self.skip_tok();
+        }
+        Ok(())
+    }
+
+    fn parse_statement_inner(&mut self, context: &Context) -> Res<Statement> {
         while self.curr_tok().kind() == token::Kind::Separator {
-            self.skip_tok()?;
+            self.skip_tok();
         }
-        Ok(match self.curr_tok().kind() {
-            token::Kind::Eoi => ast::Statement::Eoi,
-            token::Kind::Bang => {
-                self.skip_tok()?;
-                ast::Statement::Execute
-            }
+        Ok(match self.statement_kind() {
+            token::Kind::Eoi => Statement::Eoi,
+            token::Kind::Bang => self.parse_execute()?,
             token::Kind::Question => {
-                self.skip_tok()?;
-                ast::Statement::Query
+                self.skip_tok();
+                Statement::Query
             }
             token::Kind::Minus => self.parse_delete()?,
             token::Kind::Exit => {
-                self.skip_tok()?;
+                self.skip_tok();
                 self.check_sep()?;
-                ast::Statement::Exit
+                Statement::Exit
             }
             token::Kind::Help => {
-                self.skip_tok()?;
+                self.skip_tok();
+                self.check_sep()?;
+                Statement::Help
+            }
+            token::Kind::Tableau => {
+                self.skip_tok();
+                Statement::Tableau(self.parse_argument(context)?)
+            }
+            token::Kind::Deduce => {
+                self.skip_tok();
+                let formula = self.recursive_pratt(0, context)?;
+                self.check_sep()?;
+                Statement::Deduce(formula)
+            }
+            token::Kind::Sequent => self.parse_sequent(context)?,
+            token::Kind::Table => {
+                self.skip_tok();
+                let formula = self.recursive_pratt(0, context)?;
+                self.check_sep()?;
+                Statement::Table(formula)
+            }
+            kind @ (token::Kind::Nnf | token::Kind::Cnf | token::Kind::Dnf) => {
+                self.skip_tok();
+                let form = match kind {
+                    token::Kind::Nnf => ast::NormalForm::Negation,
+                    token::Kind::Cnf => ast::NormalForm::Conjunctive,
+                    _ => ast::NormalForm::Disjunctive,
+                };
+                let formula = self.recursive_pratt(0, context)?;
+                self.check_sep()?;
+                Statement::NormalForm(form, formula)
+            }
+            token::Kind::Minimize => {
+                self.skip_tok();
+                let formula = self.recursive_pratt(0, context)?;
                 self.check_sep()?;
-                ast::Statement::Help
+                Statement::Minimize(formula)
+            }
+            token::Kind::Simplify => {
+                self.skip_tok();
+                let formula = self.recursive_pratt(0, context)?;
+                self.check_sep()?;
+                Statement::Simplify(formula)
             }
             _ => {
                 let stat = self.recursive_pratt(0, context)?;
@@ -106,18 +244,31 @@ impl Parser {
         })
     }
 
+    /// the kind of the first token of a statement: a command word (e.g. `table`)
+    /// is an atom if it is followed by a binary operator, as in `table & a`
+    fn statement_kind(&self) -> token::Kind {
+        let tok = self.curr_tok();
+        match tok.kind() {
+            token::Kind::Identifier if !self.peek_tok().kind().is_binary() => {
+                token::Kind::command(&tok.literal()).unwrap_or(token::Kind::Identifier)
+            }
+            kind => kind,
+        }
+    }
+
     fn check_sep(&mut self) -> Res<()> {
         if !self.curr_tok().kind().is_sep() {
-            return Err(ParseErr::new(
-                self.curr_tok().clone(), // it uses Rc::clone() inside
-                format!("expected `{}`", token::Kind::Separator), // or Eoi
+            return Err(Error::unexpected(
+                self.curr_tok(),
+                &[token::Kind::Separator], // or Eoi
             ));
         }
-        self.skip_tok()?;
+        self.skip_tok();
         Ok(())
     }
 
-    /// it does skip the first token if it is invalid
+    /// it does skip the first token if it is invalid, unless it is a separator
+    /// (left to end the statement)
     fn recursive_pratt(&mut self, precedence: usize, context: &Context) -> Res<ast::Formula> {
         // pre: unary operator
         let mut formula = match self.curr_tok().kind() {
@@ -125,20 +276,16 @@ impl Parser {
             token::Kind::ParenL => self.parse_paren(context),
             token::Kind::Identifier => self.parse_leaf(),
             token::Kind::Number => self.parse_number(context),
-            _ => {
-                return Err(ParseErr::new(
-                    self.skip_tok()?,
-                    "not the beginning of a formula".to_string(),
-                ))
+            _ if self.curr_tok().kind().is_sep() => {
+                return Err(Error::expected_formula(self.curr_tok()))
             }
+            _ => return Err(Error::expected_formula(&self.skip_tok())),
         };
 
         // post: binary operator
         while precedence < self.curr_tok().precedence() {
             match self.curr_tok().kind() {
-                token::Kind::And | token::Kind::Or | token::Kind::Implies | token::Kind::Equiv => {
-                    formula = self.parse_binary(formula?, context)
-                }
+                kind if kind.is_binary() => formula = self.parse_binary(formula?, context),
                 _ => break,
             }
         }
@@ -147,61 +294,168 @@ impl Parser {
 
     /// it does not skip what is there instead of `)`
     fn parse_paren(&mut self, context: &Context) -> Res<ast::Formula> {
-        let paren_l = self.skip_tok()?;
+        let paren_l = self.skip_tok();
         let f = self.recursive_pratt(paren_l.precedence(), context)?;
         if self.curr_tok().kind() != token::Kind::ParenR {
-            return Err(ParseErr::new(
-                self.curr_tok().clone(), // it uses Rc::clone() inside
-                format!("expected `{}`", token::Kind::ParenR),
-            ));
+            return Err(Error::unexpected(self.curr_tok(), &[token::Kind::ParenR]));
+        }
+        let paren_r = self.skip_tok();
+        let span = paren_l.span().join(paren_r.span());
+        Ok(f.with_span(span))
+    }
+
+    /// the formula after a command, if any, then the separator
+    fn parse_argument(&mut self, context: &Context) -> Res<Option<ast::Formula>> {
+        if self.curr_tok().kind().is_sep() {
+            self.skip_tok();
+            return Ok(None);
+        }
+        let formula = self.recursive_pratt(0, context)?;
+        self.check_sep()?;
+        Ok(Some(formula))
+    }
+
+    /// `sequent`, the formulas separated by `,` on both sides of `|-`
+    fn parse_sequent(&mut self, context: &Context) -> Res<Statement> {
+        self.skip_tok();
+        let left = self.parse_formulas(token::Kind::Turnstile, context)?;
+        self.skip_tok();
+        let right = self.parse_formulas(token::Kind::Separator, context)?;
+        self.check_sep()?;
+        Ok(Statement::Sequent(Sequent::new(left, right)))
+    }
+
+    /// formulas separated by `,`, possibly none, up to `end` (not skipped)
+    fn parse_formulas(&mut self, end: token::Kind, context: &Context) -> Res<Vec<ast::Formula>> {
+        let mut formulas = Vec::new();
+        let is_end = |kind: token::Kind| kind == end || (end.is_sep() && kind.is_sep());
+        if is_end(self.curr_tok().kind()) {
+            return Ok(formulas);
+        }
+        loop {
+            formulas.push(self.recursive_pratt(0, context)?);
+            match self.curr_tok().kind() {
+                token::Kind::Comma => {
+                    self.skip_tok();
+                }
+                kind if is_end(kind) => return Ok(formulas),
+                _ => {
+                    return Err(Error::unexpected(
+                        self.curr_tok(),
+                        &[token::Kind::Comma, end],
+                    ))
+                }
+            }
+        }
+    }
+
+    /// a method after `!` must be followed by a separator
+    fn parse_execute(&mut self) -> Res<Statement> {
+        self.skip_tok();
+        if self.curr_tok().kind() != token::Kind::Identifier {
+            return Ok(Statement::Execute(None, Vec::new()));
+        }
+        let tok = self.skip_tok();
+        let method = tok
+            .literal()
+            .parse()
+            .map_err(|_| Error::unknown_method(&tok))?;
+        let atoms = match method {
+            Method::Ordered => self.parse_order()?,
+            _ => Vec::new(),
+        };
+        self.check_sep()?;
+        Ok(Statement::Execute(Some(method), atoms))
+    }
+
+    /// the atoms after `! ordered` separated by `>` or `,`, from the greatest
+    fn parse_order(&mut self) -> Res<Vec<Symbol>> {
+        let mut atoms = Vec::new();
+        if self.curr_tok().kind().is_sep() {
+            return Ok(atoms);
+        }
+        loop {
+            if self.curr_tok().kind() != token::Kind::Identifier {
+                return Err(Error::unexpected(
+                    self.curr_tok(),
+                    &[token::Kind::Identifier],
+                ));
+            }
+            let tok = self.skip_tok();
+            let atom = Symbol::intern(&tok.literal());
+            if atoms.contains(&atom) {
+                return Err(Error::repeated_atom(&tok));
+            }
+            atoms.push(atom);
+            match self.curr_tok().kind() {
+                token::Kind::Greater | token::Kind::Comma => {
+                    self.skip_tok();
+                }
+                kind if kind.is_sep() => return Ok(atoms),
+                _ => {
+                    return Err(Error::unexpected(
+                        self.curr_tok(),
+                        &[
+                            token::Kind::Greater,
+                            token::Kind::Comma,
+                            token::Kind::Separator,
+                        ],
+                    ))
+                }
+            }
         }
-        self.skip_tok()?;
-        Ok(f)
     }
 
-    fn parse_delete(&mut self) -> Res<ast::Statement> {
-        self.skip_tok()?;
+    fn parse_delete(&mut self) -> Res<Statement> {
+        self.skip_tok();
         if self.curr_tok().kind() == token::Kind::Number {
-            let n = self.skip_tok()?.literal().as_ref().parse()?;
+            let tok = self.skip_tok();
+            let n = tok
+                .literal()
+                .parse()
+                .map_err(|err| Error::invalid_number(&tok, err))?;
             self.check_sep()?;
-            Ok(ast::Statement::Delete(n))
+            Ok(Statement::Delete(n))
         } else {
-            Err(ParseErr::new(
-                self.curr_tok().clone(), // it uses Rc::clone() inside
-                format!("expected `{}`", token::Kind::Number),
-            ))
+            Err(Error::unexpected(self.curr_tok(), &[token::Kind::Number]))
         }
     }
 
     fn parse_leaf(&mut self) -> Res<ast::Formula> {
-        let t = self.skip_tok()?;
-        Ok(ast::Formula::new_leaf(t.literal()))
+        let t = self.skip_tok();
+        Ok(ast::Formula::new_leaf(Symbol::intern(&t.literal())).with_span(t.span()))
     }
 
     fn parse_number(&mut self, context: &Context) -> Res<ast::Formula> {
-        let tok = self.skip_tok()?;
-        let n: usize = tok.literal().parse()?;
+        let tok = self.skip_tok();
+        let n: usize = tok
+            .literal()
+            .parse()
+            .map_err(|err| Error::invalid_number(&tok, err))?;
         if n < context.inner().len() {
-            Ok(context.inner()[n].formula().as_ref().clone()) // it uses Rc::clone() inside
+            // only the outermost node points to the number, the others to the stored formula
+            let f = context.inner()[n].formula().as_ref().clone(); // it uses Arc::clone() inside
+            Ok(f.with_span(tok.span()))
         } else {
-            Err(ParseErr::new(
-                tok,
-                format!("{} >= {} (number of formulas)", n, context.inner().len()),
+            Err(Error::index_out_of_bound(
+                n,
+                context.inner().len(),
+                Some(tok.span()),
             ))
         }
     }
 
     fn parse_unary(&mut self, context: &Context) -> Res<ast::Formula> {
-        let operator = self.skip_tok()?.kind();
+        let tok = self.skip_tok();
+        let operator = tok.kind();
         let p = operator.precedence();
-        Ok(ast::Formula::new_unary(
-            operator,
-            self.recursive_pratt(p, context)?,
-        ))
+        let right = self.recursive_pratt(p, context)?;
+        let span = tok.span().join(right.span());
+        Ok(ast::Formula::new_unary(operator, right).with_span(span))
     }
 
     fn parse_binary(&mut self, left: ast::Formula, context: &Context) -> Res<ast::Formula> {
-        let operator = self.skip_tok()?.kind();
+        let operator = self.skip_tok().kind();
         let p = operator.precedence();
         Ok(ast::Formula::new_binary(
             left,
@@ -1,4 +1,6 @@
-use super::Parser;
+use super::{Parser, Statement};
+use crate::error::Error;
+use crate::token::{Kind, Span};
 use crate::{context, slice_to_str};
 
 #[test]
@@ -22,6 +24,26 @@ x <=> y => z | w & ~v;
 a!
 =>
 (x | y;
+! hyper
+! bogus
+! ordered
+! ordered c > b, a
+! ordered c > b > c
+! ordered c >
+! hyper c
+tableau
+tableau a & b;
+deduce a => b
+sequent a, a => b |- b
+sequent |- a | ~a
+sequent a |-
+sequent a b |- c
+table a => b
+nnf ~(a => b)
+cnf a <=> b
+dnf (a | b) & c
+minimize a & b | a & ~b
+simplify ~~a & a
 exit;
 help;
 ";
@@ -42,20 +64,39 @@ help;
         "(~((a & b) & d))",
         "EXECUTE",
         "Parse error [17:2]: got=`!` (Bang): expected `SEPARATOR`",
-        "EXECUTE",
         "Parse error [18:1]: got=`=>` (Implies): not the beginning of a formula",
         "Parse error [19:7]: got=`;` (Separator): expected `)`",
+        "EXECUTE hyper",
+        "Parse error [21:3]: got=`bogus` (Identifier): unknown method (expected one of `resolution`, `ordered`, `positive`, `negative`, `hyper`, `linear`, `input`)",
+        "EXECUTE ordered",
+        "EXECUTE ordered c > b > a",
+        "Parse error [24:19]: got=`c` (Identifier): repeated in the ordering",
+        "Parse error [25:14]: got=`\\n` (Separator): expected `IDENTIFIER`",
+        "Parse error [26:9]: got=`c` (Identifier): expected `SEPARATOR`",
+        "TABLEAU",
+        "TABLEAU (a & b)",
+        "DEDUCE (a => b)",
+        "SEQUENT a, (a => b) |- b",
+        "SEQUENT |- (a | (~a))",
+        "SEQUENT a |-",
+        "Parse error [33:11]: got=`b` (Identifier): expected `,` or `|-`",
+        "TABLE (a => b)",
+        "NNF (~(a => b))",
+        "CNF (a <=> b)",
+        "DNF ((a | b) & c)",
+        "MINIMIZE ((a & b) | (a & (~b)))",
+        "SIMPLIFY ((~(~a)) & a)",
         "EXIT",
         "HELP",
         "END OF INPUT",
     ];
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
-    let mut context = context::Context::new();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = context::Context::new();
 
     for &exp in expected {
-        let l = match pars.parse_statement(&mut context) {
+        let l = match pars.parse_statement(&context) {
             Ok(s) => format!("{s}"),
             Err(s) => format!("{s}"),
         };
@@ -77,27 +118,27 @@ x;
 ";
     let expected: &[&str] = &[
         "x
-0: x -> {{x}}",
+0: x --> {{x}}",
         "(~y)
-0: x -> {{x}}
-1: (~y) -> {{~y}}",
+0: x --> {{x}}
+1: (~y) --> {{~y}}",
         "(x => (~(~y)))
-0: x -> {{x}}
-1: (~y) -> {{~y}}
-2: (x => (~(~y))) -> {{y, ~x}}",
+0: x --> {{x}}
+1: (~y) --> {{~y}}
+2: (x => (~(~y))) --> {{y, ~x}}",
         "QUERY
-0: x -> {{x}}
-1: (~y) -> {{~y}}
-2: (x => (~(~y))) -> {{y, ~x}}",
+0: x --> {{x}}
+1: (~y) --> {{~y}}
+2: (x => (~(~y))) --> {{y, ~x}}",
         "DELETE 0
-0: (~y) -> {{~y}}
-1: (x => (~(~y))) -> {{y, ~x}}",
+0: (~y) --> {{~y}}
+1: (x => (~(~y))) --> {{y, ~x}}",
         "DELETE 1
-0: (~y) -> {{~y}}",
+0: (~y) --> {{~y}}",
     ];
 
-    let mut pars = Parser::new().unwrap();
-    pars.load_bytes(buffer.to_string()).unwrap();
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
     let mut context = context::Context::new();
 
     for &exp in expected {
@@ -110,3 +151,148 @@ x;
         }
     }
 }
+
+#[test]
+fn test_parser_errors() {
+    let buffer = "
+a b
+(a
+=> a
+-x
+7
+99999999999999999999999
+";
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = context::Context::new();
+
+    match pars.parse_statement(&context) {
+        Err(Error::UnexpectedToken {
+            found: Kind::Identifier,
+            expected,
+            span,
+            ..
+        }) => {
+            assert_eq!(expected, &[Kind::Separator]);
+            assert_eq!(span, Span::on_row(2, 3, 1));
+        }
+        other => panic!(
+            "{}",
+            other.map_or_else(|e| e.to_string(), |s| s.to_string())
+        ),
+    }
+    let tests: &[fn(&Error) -> bool] = &[
+        |e| matches!(e, Error::UnexpectedToken { .. }) && e.expected() == [Kind::ParenR],
+        |e| {
+            matches!(
+                e,
+                Error::ExpectedFormula {
+                    found: Kind::Implies,
+                    ..
+                }
+            )
+        },
+        |e| {
+            matches!(
+                e,
+                Error::UnexpectedToken {
+                    found: Kind::Identifier,
+                    ..
+                }
+            )
+        },
+        |e| {
+            matches!(
+                e,
+                Error::IndexOutOfBound {
+                    index: 7,
+                    len: 0,
+                    span: Some(_)
+                }
+            )
+        },
+        |e| matches!(e, Error::InvalidNumber { .. }),
+    ];
+    for test in tests {
+        // errors do not skip the rest of the statement
+        loop {
+            match pars.parse_statement(&context) {
+                Err(err) if test(&err) => break,
+                Ok(Statement::Eoi) => panic!("expected error not found"),
+                Err(_) | Ok(_) => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parser_recovery() {
+    let buffer = "
+a & = b; c
+x y z ! ; d
+(a | (b & ~c) ; ~d
+a &
+b
+!
+";
+    let expected: &[&str] = &[
+        "invalid token [2:5]: =",
+        "c",
+        "Parse error [3:3]: got=`y` (Identifier): expected `SEPARATOR`",
+        "d",
+        "Parse error [4:15]: got=`;` (Separator): expected `)`",
+        "(~d)",
+        "Parse error [5:4]: got=`\\n` (Separator): not the beginning of a formula",
+        "b",
+        "EXECUTE",
+        "END OF INPUT",
+    ];
+
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = context::Context::new();
+
+    for &exp in expected {
+        let l = match pars.parse_statement(&context) {
+            Ok(s) => format!("{s}"),
+            Err(s) => format!("{s}"),
+        };
+        if exp != l {
+            panic!("expected=`{exp}`\ngot     =`{l}`")
+        }
+    }
+}
+
+#[test]
+fn test_parser_commands() {
+    // the command words are atoms, but at the start of a statement
+    let buffer = "
+table & cnf
+a | table => simplify
+cnf table | dnf
+tableau
+(table)
+";
+    let expected: &[&str] = &[
+        "(table & cnf)",
+        "((a | table) => simplify)",
+        "CNF (table | dnf)",
+        "TABLEAU",
+        "table",
+        "END OF INPUT",
+    ];
+
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = context::Context::new();
+
+    for &exp in expected {
+        let l = match pars.parse_statement(&context) {
+            Ok(s) => format!("{s}"),
+            Err(s) => format!("{s}"),
+        };
+        if exp != l {
+            panic!("expected=`{exp}`\ngot     =`{l}`")
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::clause::{Clause, ClauseId, Lit, SetClauses};
+use crate::model::Model;
+use crate::symbol::Symbol;
+use std::collections::HashSet;
+
+#[cfg(test)]
+mod test;
+
+/// something removed by the preprocessing
+#[derive(Debug, Clone)]
+enum Step {
+    /// the clauses with a literal whose opposite is never used
+    Pure(Lit, Vec<Clause>),
+    /// the clauses with the symbol, replaced by all their resolvents on it
+    Eliminated(Symbol, Vec<Clause>),
+}
+
+/// Davis-Putnam preprocessing: it eliminates the pure literals, and the symbols
+/// whose resolvents are not more than the clauses they replace. The clauses
+/// removed are only marked as eliminated (see `SetClauses::eliminate()`), and
+/// they are remembered so a model can be rebuilt with `extend_model()`
+#[derive(Debug, Clone, Default)]
+pub struct Preprocessor {
+    steps: Vec<Step>,
+}
+
+impl Preprocessor {
+    pub fn new() -> Preprocessor {
+        Preprocessor::default()
+    }
+
+    /// the symbols eliminated so far (also the ones of the pure literals)
+    pub fn eliminated(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.steps.iter().map(|step| match step {
+            Step::Pure(lit, _) => lit.symbol(),
+            Step::Eliminated(symbol, _) => *symbol,
+        })
+    }
+    /// true if the preprocessing is no longer valid when `lits` are added
+    pub fn is_affected_by(&self, lits: &[Lit]) -> bool {
+        let eliminated: HashSet<Symbol> = self.eliminated().collect();
+        lits.iter().any(|lit| eliminated.contains(&lit.symbol()))
+    }
+
+    /// eliminates what it can from the clauses, until nothing changes;
+    /// returns true if the box is found
+    pub fn run(&mut self, set: &mut SetClauses) -> bool {
+        loop {
+            let mut changed = false;
+            // in order of name, so the result does not depend on when they are interned
+            let mut symbols: Vec<Symbol> = set
+                .active()
+                .flat_map(|id| set.clause(id).iter().map(|x| x.symbol()))
+                .collect::<HashSet<Symbol>>()
+                .into_iter()
+                .collect();
+            symbols.sort();
+            for symbol in symbols {
+                let positive = active_occurrences(set, Lit::positive(symbol));
+                let negative = active_occurrences(set, Lit::negative(symbol));
+                if positive.is_empty() && negative.is_empty() {
+                    // already eliminated with another symbol
+                    continue;
+                }
+                if positive.is_empty() || negative.is_empty() {
+                    let lit = if positive.is_empty() {
+                        Lit::negative(symbol)
+                    } else {
+                        Lit::positive(symbol)
+                    };
+                    let clauses = eliminate(set, positive.into_iter().chain(negative));
+                    self.steps.push(Step::Pure(lit, clauses));
+                    changed = true;
+                    continue;
+                }
+                let pairs: Vec<(ClauseId, ClauseId)> = positive
+                    .iter()
+                    .flat_map(|&p| negative.iter().map(move |&n| (p, n)))
+                    .filter(|&(p, n)| set.resolvent(p, n).is_some())
+                    .collect();
+                if pairs.len() > positive.len() + negative.len() {
+                    continue;
+                }
+                let mut found = false;
+                for (p, n) in pairs {
+                    let id = set.insert_resolvent(p, n).expect("checked above");
+                    found |= set.clause(id).is_empty();
+                }
+                let clauses = eliminate(set, positive.into_iter().chain(negative));
+                self.steps.push(Step::Eliminated(symbol, clauses));
+                if found {
+                    return true;
+                }
+                changed = true;
+            }
+            if !changed {
+                return false;
+            }
+        }
+    }
+
+    /// `model` must be a model of the clauses left: it is extended to the
+    /// symbols eliminated, undoing the steps in reverse order
+    pub fn extend_model(&self, model: &mut Model) {
+        for step in self.steps.iter().rev() {
+            match step {
+                Step::Pure(lit, clauses) => {
+                    model.set_lit(*lit);
+                    assign_free(model, clauses);
+                }
+                Step::Eliminated(symbol, clauses) => {
+                    model.set(*symbol, false);
+                    assign_free(model, clauses);
+                    let positive = Lit::positive(*symbol);
+                    // if it were false one of these clauses would be false
+                    let value = clauses.iter().any(|c| {
+                        c.lits().contains(&positive)
+                            && c.lits()
+                                .iter()
+                                .all(|&x| x == positive || model.lit_value(x) == Some(false))
+                    });
+                    model.set(*symbol, value);
+                }
+            }
+        }
+    }
+}
+
+/// marks the clauses as eliminated, returns them
+fn eliminate(set: &mut SetClauses, ids: impl Iterator<Item = ClauseId>) -> Vec<Clause> {
+    ids.map(|id| {
+        set.eliminate(id);
+        Clause::from(set.clause(id).to_vec())
+    })
+    .collect()
+}
+
+/// the symbols never constrained by the clauses left are false
+fn assign_free(model: &mut Model, clauses: &[Clause]) {
+    for lit in clauses.iter().flat_map(|c| c.lits()) {
+        if model.value(lit.symbol()).is_none() {
+            model.set(lit.symbol(), false);
+        }
+    }
+}
+
+fn active_occurrences(set: &SetClauses, lit: Lit) -> Vec<ClauseId> {
+    set.occurrences(lit)
+        .iter()
+        .copied()
+        .filter(|&id| !set.is_eliminated(id))
+        .collect()
+}
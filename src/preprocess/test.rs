@@ -0,0 +1,74 @@
+use super::Preprocessor;
+use crate::clause::{Lit, SetClauses};
+use crate::context::Context;
+use crate::parser::Parser;
+use crate::solver::Solver;
+use crate::{parser::Statement, symbol::Symbol};
+
+fn clauses(buffer: &str) -> SetClauses {
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let mut context = Context::new();
+    while !matches!(
+        pars.parse_statement_update_context(&mut context).unwrap(),
+        Statement::Eoi
+    ) {}
+    SetClauses::from(&context)
+}
+
+#[test]
+fn test_preprocess() {
+    let mut set = clauses("a | b; ~a | c; ~b | c; ~c | d; d | e");
+    let mut pre = Preprocessor::new();
+    assert!(!pre.run(&mut set));
+    // everything can be eliminated
+    assert_eq!(set.active().count(), 0);
+    let mut model = set.model();
+    pre.extend_model(&mut model);
+    for id in set.ids().filter(|&id| set.parents(id).is_none()) {
+        assert!(model.satisfies(set.clause(id)), "{model}");
+    }
+    let (c, x) = (Symbol::intern("c"), Symbol::intern("pre_x"));
+    assert!(pre.is_affected_by(&[Lit::negative(c)]));
+    assert!(!pre.is_affected_by(&[Lit::negative(x)]));
+
+    let mut set = clauses("a | b; ~a | b; a | ~b; ~a | ~b");
+    assert!(Preprocessor::new().run(&mut set));
+    assert!(!set.trace_from_box().is_empty());
+}
+
+#[test]
+fn test_preprocess_solver() {
+    let tests = &[
+        ("(~B|C) & ~(A&~B) & (A|((B|C)&~C)); ~(A&B&C)", true),
+        ("(~(B&C)) & (A=>(C<=>B)) & (~C=>A) & (~B|(A=>~C));", false),
+        (
+            "(p | q) & (~p | r) & (~q | r) & (~r | s | t) & (~s | ~t) & (s | ~u)",
+            false,
+        ),
+        ("(p <=> q) & (q <=> r) & (r <=> ~p)", true),
+        (
+            "x1 | x2 | x3; ~x1 | x2; ~x2 | x3; ~x3 | x1; ~x1 | ~x2 | ~x3",
+            true,
+        ),
+    ];
+    for (buffer, exp) in tests {
+        let input = clauses(buffer);
+        for (preprocess, propagate) in [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let mut solver = Solver::new();
+            solver.set_preprocess(preprocess);
+            solver.set_propagate(propagate);
+            solver.add(&input);
+            assert_eq!(solver.solve(), *exp, "{buffer}");
+            match solver.model() {
+                Some(model) => {
+                    for id in input.ids() {
+                        assert!(model.satisfies(input.clause(id)), "{buffer}: {model}");
+                    }
+                }
+                None => assert!(!solver.proof().is_empty()),
+            }
+        }
+    }
+}
@@ -1,24 +1,57 @@
-use crate::ast::Statement;
-use crate::clause::SetClauses;
+use crate::ast::Formula;
+use crate::clause::Restriction;
+use crate::cli::{self, Format, Input, Options};
 use crate::context;
-use crate::error::Res;
+use crate::deduction::{self, Proof};
+use crate::diagnostic;
+use crate::error::{Error, Res};
+use crate::fragment::Fragment;
 use crate::help;
-use crate::parser;
-use crate::slice_to_str;
-use std::fs::File; use std::io::Read;
+use crate::minimize::Minimization;
+use crate::model::Model;
+use crate::order::Order;
+use crate::parser::{self, Statement};
+use crate::sequent::{Derivation, Sequent};
+use crate::simplify::Simplification;
+use crate::solver::Method;
+use crate::symbol::Symbol;
+use crate::table::TruthTable;
+use crate::tableau::Tableau;
+use crate::{json_list, json_str, slice_to_str};
+use std::fs::File;
+use std::io::Read;
 use std::io::{self, Write};
 
+#[cfg(test)]
+mod test;
+
 const PROMPT: &str = ">> ";
 
-pub fn repl() -> Res<()> {
+/// what happened while evaluating the input so far
+#[derive(Default)]
+struct Status {
+    /// name of the input being evaluated, empty in the REPL
+    input: String,
+    exit: bool,
+    errors: usize,
+    /// result of the last `!`
+    box_found: Option<bool>,
+    /// true if the method of the last `!` missed the box
+    missed: bool,
+}
+
+pub fn repl(options: &Options) -> Res<()> {
     let stdin = io::stdin();
-    let mut pars = parser::Parser::new()?;
+    let mut pars = parser::Parser::new();
     let mut context = context::Context::new();
+    context.set_simplify(options.simplify());
+    let mut status = Status::default();
     println!("Type `help`");
     print!("{}", PROMPT);
     io::stdout().flush()?;
     for line in stdin.lines() {
-        if eval_print(&mut pars, line?, &mut context)? {
+        eval_print(&mut pars, line?, &mut context, options, &mut status)?;
+        if status.exit {
             io::stdout().flush()?; // do i need this here?
             break;
         }
@@ -28,47 +61,446 @@ pub fn repl() -> Res<()> {
     Ok(())
 }
 
-pub fn rep(filename: &str) -> Res<()> {
-    let mut file = File::open(filename)?;
-    let mut buf = String::new();
-    file.read_to_string(&mut buf)?;
-    let mut pars = parser::Parser::new()?;
+/// evaluates every input of `options` in the same context, returns the exit code
+/// of the last `!`: if no `!` is found in the input the formulas are solved at
+/// the end (unless only checking the input)
+pub fn batch(options: &Options) -> Res<i32> {
+    let mut pars = parser::Parser::new();
     let mut context = context::Context::new();
-    eval_print(&mut pars, buf, &mut context)?;
-    Ok(())
+    context.set_simplify(options.simplify());
+    let mut status = Status::default();
+    for input in options.inputs() {
+        status.input = input.name().to_string();
+        let buf = match read_input(input) {
+            Ok(buf) => buf,
+            Err(err) => {
+                eprintln!("{}: {}", input.name(), err);
+                status.errors += 1;
+                continue;
+            }
+        };
+        pars.reset_position();
+        let errors = status.errors;
+        eval_print(&mut pars, buf, &mut context, options, &mut status)?;
+        summarize(input, status.errors - errors, options);
+        if status.exit {
+            break;
+        }
+    }
+    if status.errors > 0 {
+        return Ok(cli::EXIT_ERROR);
+    }
+    if options.check() {
+        return Ok(cli::EXIT_OK);
+    }
+    if status.box_found.is_none() {
+        execute(&mut context, options.method(), &[], options, &mut status);
+    }
+    Ok(match status.box_found {
+        Some(true) => cli::EXIT_UNSAT,
+        _ if status.missed => cli::EXIT_UNKNOWN,
+        _ => cli::EXIT_SAT,
+    })
+}
+
+fn summarize(input: &Input, errors: usize, options: &Options) {
+    match errors {
+        0 if options.check() && !options.quiet() => println!("{}: no errors found", input.name()),
+        0 => {}
+        1 => eprintln!("{}: 1 error found", input.name()),
+        n => eprintln!("{}: {n} errors found", input.name()),
+    }
+}
+
+fn read_input(input: &Input) -> Res<String> {
+    let mut buf = String::new();
+    match input {
+        Input::File(filename) => {
+            File::open(filename)?.read_to_string(&mut buf)?;
+        }
+        Input::Stdin => {
+            io::stdin().read_to_string(&mut buf)?;
+        }
+        Input::Expr(expr) => buf.push_str(expr),
+    }
+    Ok(buf)
 }
 
-/// returns true if exit is read
 fn eval_print(
     pars: &mut parser::Parser,
     line: String,
     context: &mut context::Context,
-) -> Res<bool> {
-    if let Err(err) = pars.load_bytes(line) {
-        eprintln!("{}", err);
+    options: &Options,
+    status: &mut Status,
+) -> Res<()> {
+    pars.load_bytes(line);
+    let json = options.format() == Format::Json;
+    loop {
+        match pars.parse_statement_update_context(context) {
+            Ok(Statement::Eoi) => break,
+            Ok(Statement::Exit) => {
+                status.exit = true;
+                break;
+            }
+            Ok(Statement::Execute(..)) if options.check() => {}
+            Ok(statement)
+                if (options.quiet() || options.check()) && statement_is_silent(&statement) => {}
+            Ok(Statement::Help) if json => println!("{{\"help\":{}}}", json_str(help::help())),
+            Ok(Statement::Help) => println!("{}", help::help()),
+            Ok(Statement::Delete(n)) if json => println!("{{\"removed\":{n}}}"),
+            Ok(Statement::Delete(n)) => println!("Formula {n} removed."),
+            Ok(Statement::Query) if json => {
+                println!("{{\"formulas\":{}}}", json_list(&context.vec_str()))
+            }
+            Ok(Statement::Query) => println!("{}", slice_to_str(&context.vec_str())),
+            Ok(Statement::Execute(method, atoms)) => execute(
+                context,
+                method.unwrap_or(options.method()),
+                &atoms,
+                options,
+                status,
+            ),
+            Ok(Statement::Tableau(formula)) => tableau(context, formula, json),
+            Ok(Statement::Deduce(goal)) => deduce(context, &goal, json),
+            Ok(Statement::Sequent(sequent)) => sequent_calculus(sequent, json),
+            Ok(Statement::Table(formula)) => truth_table(&formula, options.table_limit(), json),
+            Ok(Statement::NormalForm(form, formula)) => match formula.normal_form(form) {
+                Ok(formula) if json => println!(
+                    "{{\"form\":{},\"formula\":{}}}",
+                    json_str(form.as_str()),
+                    json_str(&formula.to_string())
+                ),
+                Ok(formula) => println!("{formula}"),
+                Err(err) => report(pars, status, &err),
+            },
+            Ok(Statement::Minimize(formula)) => minimize(&formula, options.table_limit(), json),
+            Ok(Statement::Simplify(formula)) => simplify(&formula, json),
+            Ok(Statement::Formula(formula)) if json => {
+                println!("{{\"formula\":{}}}", json_str(&formula.to_string()))
+            }
+            Ok(Statement::Formula(formula)) => println!("{}", formula),
+            Err(err) => report(pars, status, &err),
+        }
+    }
+    Ok(())
+}
+
+/// statements that only print something
+fn statement_is_silent(statement: &Statement) -> bool {
+    !matches!(statement, Statement::Execute(..))
+}
+
+fn report(pars: &parser::Parser, status: &mut Status, err: &Error) {
+    status.errors += 1;
+    let line = err.span().and_then(|s| pars.source_line(s.start().row()));
+    eprintln!("{}", diagnostic::render(err, &status.input, line));
+}
+
+/// the tableau of `formula`, or else of the formulas of the context
+fn tableau(context: &context::Context, formula: Option<Formula>, json: bool) {
+    let formulas = match formula {
+        Some(formula) => vec![formula],
+        None => context
+            .inner()
+            .iter()
+            .map(|f| f.formula().as_ref().clone())
+            .collect(),
+    };
+    let tableau = Tableau::new(&formulas);
+    if json {
+        let mut fields = vec![
+            format!("\"tableau\":{}", json_list(&tableau.lines())),
+            format!("\"closed\":{}", tableau.is_closed()),
+        ];
+        if let Some(model) = tableau.model() {
+            fields.push(format!("\"model\":{}", json_list(&model.lits())));
+        }
+        println!("{{{}}}", fields.join(","));
+        return;
+    }
+    print!("{tableau}");
+    match tableau.model() {
+        Some(model) => println!("Open: satisfiable, model {model}"),
+        None => println!("Closed: unsatisfiable"),
+    }
+}
+
+/// a natural deduction proof of `goal` from the formulas of the context
+fn deduce(context: &context::Context, goal: &Formula, json: bool) {
+    let premises: Vec<Formula> = context
+        .inner()
+        .iter()
+        .map(|f| f.formula().as_ref().clone())
+        .collect();
+    match Proof::search(&premises, goal, deduction::LIMIT) {
+        Ok(proof) if json => {
+            let text = proof.to_string();
+            let lines: Vec<&str> = text.lines().collect();
+            println!("{{\"deduction\":{}}}", json_list(&lines));
+        }
+        Ok(proof) => print!("{proof}"),
+        Err(failure) if json => println!(
+            "{{\"deduction\":null,\"reason\":{}}}",
+            json_str(&failure.to_string())
+        ),
+        Err(failure) => println!("No proof: {failure}"),
+    }
+}
+
+/// the derivation of `sequent`, as a tree and for LaTeX
+fn sequent_calculus(sequent: Sequent, json: bool) {
+    let derivation = Derivation::prove(sequent);
+    let counter_model = derivation.counter_model();
+    if json {
+        let mut fields = vec![
+            format!("\"derivation\":{}", json_list(&derivation.lines())),
+            format!("\"valid\":{}", derivation.is_valid()),
+            format!("\"latex\":{}", json_str(&derivation.latex())),
+        ];
+        if let Some(model) = counter_model {
+            fields.push(format!("\"counterexample\":{}", json_list(&model.lits())));
+        }
+        println!("{{{}}}", fields.join(","));
+        return;
+    }
+    print!("{derivation}");
+    match counter_model {
+        Some(model) => println!("Not valid: counterexample {model}"),
+        None => println!("Valid"),
+    }
+    print!("{}", derivation.latex());
+}
+
+/// the truth table of `formula`, if it has at most `limit` atoms
+fn truth_table(formula: &Formula, limit: usize, json: bool) {
+    let table = match TruthTable::new(formula, limit) {
+        Ok(table) => table,
+        Err(err) if json => {
+            println!(
+                "{{\"table\":null,\"reason\":{}}}",
+                json_str(&err.to_string())
+            );
+            return;
+        }
+        Err(err) => {
+            println!("No table: {err} (see `--table-limit`)");
+            return;
+        }
+    };
+    let true_rows = table.true_rows();
+    if json {
+        let rows: Vec<String> = table
+            .rows()
+            .iter()
+            .map(|row| {
+                let values: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+                format!("[{}]", values.join(","))
+            })
+            .collect();
+        let true_rows: Vec<String> = true_rows.iter().map(|r| r.to_string()).collect();
+        println!(
+            "{{\"columns\":{},\"rows\":[{}],\"true_rows\":[{}]}}",
+            json_list(&table.headers()),
+            rows.join(","),
+            true_rows.join(",")
+        );
+        return;
+    }
+    print!("{table}");
+    let verdict = match true_rows.len() {
+        0 => "unsatisfiable",
+        n if n == table.rows().len() => "valid",
+        _ => "satisfiable",
+    };
+    println!(
+        "True in {} of {} rows: {verdict}",
+        true_rows.len(),
+        table.rows().len()
+    );
+}
+
+/// the prime implicants and the minimal forms of `formula`, if it has at most
+/// `limit` atoms
+fn minimize(formula: &Formula, limit: usize, json: bool) {
+    let minimization = match Minimization::new(formula, limit) {
+        Ok(minimization) => minimization,
+        Err(err) if json => {
+            println!(
+                "{{\"minimized\":null,\"reason\":{}}}",
+                json_str(&err.to_string())
+            );
+            return;
+        }
+        Err(err) => {
+            println!("No minimization: {err} (see `--table-limit`)");
+            return;
+        }
+    };
+    let primes: Vec<Formula> = minimization
+        .primes()
+        .iter()
+        .map(|p| minimization.implicant(p))
+        .collect();
+    let sum = minimization.sum_of_products();
+    let product = minimization.product_of_sums();
+    let equivalent = minimization.is_equivalent(formula);
+    if json {
+        println!(
+            "{{\"primes\":{},\"sum_of_products\":{},\"product_of_sums\":{},\"equivalent\":{equivalent}}}",
+            json_list(&primes),
+            json_str(&sum.to_string()),
+            json_str(&product.to_string())
+        );
+        return;
+    }
+    let primes: Vec<String> = primes.iter().map(|p| p.to_string()).collect();
+    if primes.is_empty() {
+        println!("Prime implicants: none");
+    } else {
+        println!("Prime implicants: {}", primes.join(", "));
+    }
+    println!("Sum of products: {sum}");
+    println!("Product of sums: {product}");
+    if equivalent {
+        println!("Equivalent to {formula}");
     } else {
-        loop {
-            match pars.parse_statement_update_context(context) {
-                Ok(Statement::Eoi) => break,
-                Ok(Statement::Exit) => return Ok(true),
-                Ok(Statement::Help) => println!("{}", help::help()),
-                Ok(Statement::Delete(n)) => println!("Formula {n} removed."),
-                Ok(Statement::Query) => println!("{}", slice_to_str(&context.vec_str())),
-                Ok(Statement::Execute) => {
-                    let mut to_solve = SetClauses::from(&*context);
-                    if to_solve.find_box() {
-                        println!("Box found:");
-                        println!("{}", slice_to_str(&context.vec_str()));
-                        println!("Proof:");
-                        println!("{}", slice_to_str(&to_solve.trace_from_box()));
-                    } else {
-                        println!("Box not found.");
-                    }
+        println!("NOT equivalent to {formula}");
+    }
+}
+
+/// the steps that simplify `formula`, then the result
+fn simplify(formula: &Formula, json: bool) {
+    let simplification = Simplification::new(formula);
+    if json {
+        println!(
+            "{{\"steps\":{},\"simplified\":{}}}",
+            json_list(simplification.steps()),
+            json_str(&simplification.result().to_string())
+        );
+        return;
+    }
+    for (i, step) in simplification.steps().iter().enumerate() {
+        println!("{}. {step}", i + 1);
+    }
+    println!("Simplified: {}", simplification.result());
+}
+
+/// what `!` found
+struct Outcome {
+    found: bool,
+    proof: Vec<String>,
+    model: Option<Model>,
+    /// the fragment solved by a special algorithm
+    fragment: Option<Fragment>,
+    /// the ordering of the atoms, with ordered resolution
+    ordering: Option<String>,
+    /// false if the method missed the box: the result is unknown
+    complete: bool,
+}
+
+impl Outcome {
+    /// how the result was found, if not by plain resolution
+    fn print_strategy(&self) {
+        if let Some(fragment) = self.fragment {
+            println!("Fragment: {fragment}");
+        }
+        if let Some(ordering) = &self.ordering {
+            println!("Ordering: {ordering}");
+        }
+    }
+}
+
+/// `method` is the one given after `!`, or else the one of the options;
+/// `atoms` is the ordering of `ordered`, the one of the options if empty
+fn execute(
+    context: &mut context::Context,
+    method: Method,
+    atoms: &[Symbol],
+    options: &Options,
+    status: &mut Status,
+) {
+    let restriction = match method {
+        Method::Resolution => Restriction::None,
+        Method::Ordered if atoms.is_empty() => Restriction::Ordered(options.order()),
+        Method::Ordered => {
+            Restriction::Ordered(Order::new(atoms.to_vec(), options.order().selection()))
+        }
+        Method::Positive => Restriction::Positive,
+        Method::Negative => Restriction::Negative,
+        Method::Hyper => Restriction::Hyper,
+        Method::Linear => Restriction::Linear,
+        Method::Input => Restriction::Input,
+    };
+    let solver = context.solver_mut();
+    solver.set_restriction(restriction);
+    solver.set_jobs(options.jobs());
+    solver.set_preprocess(options.preprocess());
+    solver.set_propagate(options.propagate());
+    solver.set_fragments(options.fragments());
+    let found = context.solve();
+    let solver = context.solver();
+    let outcome = Outcome {
+        found,
+        proof: solver.proof().to_vec(),
+        model: solver.model().cloned(),
+        fragment: Some(solver.fragment()).filter(|&f| f != Fragment::General),
+        ordering: solver.ranking().map(|r| r.to_string()),
+        complete: solver.is_complete(),
+    };
+    status.box_found = Some(outcome.found);
+    status.missed = !outcome.complete;
+    if options.quiet() {
+        return;
+    }
+    match options.format() {
+        Format::Json => {
+            let result = match (outcome.found, outcome.complete) {
+                (true, _) => "unsat",
+                (false, true) => "sat",
+                (false, false) => "unknown",
+            };
+            let mut fields = vec![format!("\"result\":\"{result}\"")];
+            if let Some(fragment) = outcome.fragment {
+                fields.push(format!("\"fragment\":{}", json_str(fragment.as_str())));
+            }
+            if let Some(ordering) = &outcome.ordering {
+                fields.push(format!("\"ordering\":{}", json_str(ordering)));
+            }
+            if !outcome.complete {
+                fields.push("\"complete\":false".to_string());
+            }
+            if options.proof() {
+                if outcome.found {
+                    fields.push(format!("\"formulas\":{}", json_list(&context.vec_str())));
+                    fields.push(format!("\"proof\":{}", json_list(&outcome.proof)));
+                } else if let Some(model) = &outcome.model {
+                    fields.push(format!("\"model\":{}", json_list(&model.lits())));
                 }
-                Ok(Statement::Formula(formula)) => println!("{}", formula),
-                Err(err) => eprintln!("{}", err),
             }
+            println!("{{{}}}", fields.join(","));
         }
-    };
-    Ok(false)
+        Format::Text if outcome.found => {
+            println!("Box found:");
+            println!("{}", slice_to_str(&context.vec_str()));
+            outcome.print_strategy();
+            if options.proof() && outcome.proof.is_empty() {
+                // a formula has been simplified to `false`
+                println!("Proof: the box is one of the clauses");
+            } else if options.proof() {
+                println!("Proof:");
+                println!("{}", slice_to_str(&outcome.proof));
+            }
+        }
+        Format::Text if !outcome.complete => {
+            println!("Unknown: the method missed the box, but there is no model.");
+            outcome.print_strategy();
+        }
+        Format::Text => {
+            println!("Box not found.");
+            outcome.print_strategy();
+            if let (Some(model), true) = (&outcome.model, options.proof()) {
+                println!("Model: {model}");
+            }
+        }
+    }
 }
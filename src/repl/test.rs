@@ -0,0 +1,26 @@
+use super::batch;
+use crate::cli::{self, Options};
+
+fn exit_code(args: &[&str]) -> i32 {
+    let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    batch(&Options::parse(&args).unwrap()).unwrap()
+}
+
+#[test]
+fn test_batch_exit_code() {
+    let unsat = [
+        "-e", "a | b", "-e", "a | ~b", "-e", "~a | b", "-e", "~a | ~b",
+    ];
+    let sat = ["-e", "a | b", "-e", "a | ~b", "-e", "~a | b"];
+    let run = |solver: &str, exprs: &[&str]| {
+        let mut args = vec!["-q", "--solver", solver];
+        args.extend_from_slice(exprs);
+        exit_code(&args)
+    };
+    assert_eq!(run("resolution", &unsat), cli::EXIT_UNSAT);
+    assert_eq!(run("resolution", &sat), cli::EXIT_SAT);
+    // input resolution misses the box of clauses that are not Horn
+    assert_eq!(run("input", &unsat), cli::EXIT_UNKNOWN);
+    assert_eq!(run("input", &sat), cli::EXIT_SAT);
+    assert_eq!(run("linear", &unsat), cli::EXIT_UNSAT);
+}
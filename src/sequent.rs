@@ -0,0 +1,335 @@
+use crate::ast::Formula;
+use crate::model::Model;
+use crate::token::Kind;
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// `left |- right`: if all the formulas on the left are true, one on the right is
+#[derive(Debug, Clone, Default)]
+pub struct Sequent {
+    left: Vec<Formula>,
+    right: Vec<Formula>,
+}
+
+impl Sequent {
+    pub fn new(left: Vec<Formula>, right: Vec<Formula>) -> Sequent {
+        Sequent { left, right }
+    }
+    pub fn left(&self) -> &[Formula] {
+        &self.left
+    }
+    pub fn right(&self) -> &[Formula] {
+        &self.right
+    }
+
+    /// the first compound formula, preferring the ones whose rule does not
+    /// branch: its side (true if left), index and rule
+    fn principal(&self) -> Option<(bool, usize, Rule)> {
+        let left = self.left.iter().enumerate().map(|(i, f)| (true, i, f));
+        let right = self.right.iter().enumerate().map(|(i, f)| (false, i, f));
+        let rules: Vec<(bool, usize, Rule)> = left
+            .chain(right)
+            .filter_map(|(side, i, f)| Some((side, i, Rule::of(f, side)?)))
+            .collect();
+        let first = rules.first().copied();
+        rules.into_iter().find(|r| !r.2.branches()).or(first)
+    }
+
+    /// true if an atom is on both sides
+    fn is_axiom(&self) -> bool {
+        self.left.iter().any(|l| match l {
+            Formula::Leaf(a) => self
+                .right
+                .iter()
+                .any(|r| matches!(r, Formula::Leaf(b) if a.symbol() == b.symbol())),
+            _ => false,
+        })
+    }
+
+    /// the premises of the rule applied to the formula at `i` of a side
+    fn premises(&self, left: bool, i: usize) -> Vec<Sequent> {
+        let mut rest = self.clone();
+        let f = if left {
+            rest.left.remove(i)
+        } else {
+            rest.right.remove(i)
+        };
+        // the new formulas are added in front, where they are easy to see
+        let with = |l: &[&Formula], r: &[&Formula]| {
+            let mut s = rest.clone();
+            s.left.splice(0..0, l.iter().map(|&f| f.clone()));
+            s.right.splice(0..0, r.iter().map(|&f| f.clone()));
+            s
+        };
+        match (&f, left) {
+            (Formula::Unary(u), true) => vec![with(&[], &[u.right()])],
+            (Formula::Unary(u), false) => vec![with(&[u.right()], &[])],
+            (Formula::Binary(b), _) => match (b.parts(), left) {
+                ((a, Kind::And, b), true) => vec![with(&[a, b], &[])],
+                ((a, Kind::And, b), false) => vec![with(&[], &[a]), with(&[], &[b])],
+                ((a, Kind::Or, b), true) => vec![with(&[a], &[]), with(&[b], &[])],
+                ((a, Kind::Or, b), false) => vec![with(&[], &[a, b])],
+                ((a, Kind::Implies, b), true) => vec![with(&[], &[a]), with(&[b], &[])],
+                ((a, Kind::Implies, b), false) => vec![with(&[a], &[b])],
+                ((a, Kind::Equiv, b), true) => vec![with(&[a, b], &[]), with(&[], &[a, b])],
+                ((a, Kind::Equiv, b), false) => vec![with(&[a], &[b]), with(&[b], &[a])],
+                ((_, operator, _), _) => unreachable!("not a binary operator: {operator:?}"),
+            },
+            (Formula::Leaf(_), _) => unreachable!("an atom has no rule"),
+        }
+    }
+}
+
+fn join(formulas: &[Formula], f: impl Fn(&Formula) -> String) -> String {
+    formulas.iter().map(f).collect::<Vec<_>>().join(", ")
+}
+
+/// e.g. `a, (a => b) |- b`
+impl fmt::Display for Sequent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let left = join(&self.left, |f| f.to_string());
+        let right = join(&self.right, |f| f.to_string());
+        match (left.is_empty(), right.is_empty()) {
+            (true, true) => write!(f, "|-"),
+            (true, false) => write!(f, "|- {right}"),
+            (false, true) => write!(f, "{left} |-"),
+            (false, false) => write!(f, "{left} |- {right}"),
+        }
+    }
+}
+
+/// the rules of the cut-free calculus G3: `L` for a formula on the left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Axiom,
+    NotL,
+    NotR,
+    AndL,
+    AndR,
+    OrL,
+    OrR,
+    ImpliesL,
+    ImpliesR,
+    EquivL,
+    EquivR,
+}
+
+impl Rule {
+    /// the rule of `f` on a side (true if left), None for an atom
+    fn of(f: &Formula, left: bool) -> Option<Rule> {
+        let kind = match f {
+            Formula::Leaf(_) => return None,
+            Formula::Unary(_) => Kind::Not,
+            Formula::Binary(b) => b.parts().1,
+        };
+        Some(match (kind, left) {
+            (Kind::Not, true) => Rule::NotL,
+            (Kind::Not, false) => Rule::NotR,
+            (Kind::And, true) => Rule::AndL,
+            (Kind::And, false) => Rule::AndR,
+            (Kind::Or, true) => Rule::OrL,
+            (Kind::Or, false) => Rule::OrR,
+            (Kind::Implies, true) => Rule::ImpliesL,
+            (Kind::Implies, false) => Rule::ImpliesR,
+            (Kind::Equiv, true) => Rule::EquivL,
+            (_, _) => Rule::EquivR,
+        })
+    }
+    fn branches(&self) -> bool {
+        matches!(
+            self,
+            Rule::AndR | Rule::OrL | Rule::ImpliesL | Rule::EquivL | Rule::EquivR
+        )
+    }
+    pub fn as_str(&self) -> &str {
+        match self {
+            Rule::Axiom => "Ax",
+            Rule::NotL => "~L",
+            Rule::NotR => "~R",
+            Rule::AndL => "&L",
+            Rule::AndR => "&R",
+            Rule::OrL => "|L",
+            Rule::OrR => "|R",
+            Rule::ImpliesL => "=>L",
+            Rule::ImpliesR => "=>R",
+            Rule::EquivL => "<=>L",
+            Rule::EquivR => "<=>R",
+        }
+    }
+    fn latex(&self) -> &str {
+        match self {
+            Rule::Axiom => "\\mathrm{Ax}",
+            Rule::NotL => "\\lnot L",
+            Rule::NotR => "\\lnot R",
+            Rule::AndL => "\\land L",
+            Rule::AndR => "\\land R",
+            Rule::OrL => "\\lor L",
+            Rule::OrR => "\\lor R",
+            Rule::ImpliesL => "\\to L",
+            Rule::ImpliesR => "\\to R",
+            Rule::EquivL => "\\leftrightarrow L",
+            Rule::EquivR => "\\leftrightarrow R",
+        }
+    }
+}
+
+/// the tree of a sequent: every rule of G3 is invertible, so the sequent is
+/// valid exactly when every leaf is an axiom
+#[derive(Debug, Clone)]
+pub struct Derivation {
+    sequent: Sequent,
+    /// None for a leaf with only atoms and no axiom
+    rule: Option<Rule>,
+    premises: Vec<Derivation>,
+}
+
+impl Derivation {
+    /// applies the rules until only atoms are left, the ones that do not
+    /// branch first
+    pub fn prove(sequent: Sequent) -> Derivation {
+        if sequent.is_axiom() {
+            return Derivation {
+                sequent,
+                rule: Some(Rule::Axiom),
+                premises: Vec::new(),
+            };
+        }
+        match sequent.principal() {
+            Some((left, i, rule)) => Derivation {
+                premises: sequent
+                    .premises(left, i)
+                    .into_iter()
+                    .map(Derivation::prove)
+                    .collect(),
+                sequent,
+                rule: Some(rule),
+            },
+            None => Derivation {
+                sequent,
+                rule: None,
+                premises: Vec::new(),
+            },
+        }
+    }
+    pub fn sequent(&self) -> &Sequent {
+        &self.sequent
+    }
+    pub fn rule(&self) -> Option<Rule> {
+        self.rule
+    }
+    pub fn premises(&self) -> &[Derivation] {
+        &self.premises
+    }
+    pub fn is_valid(&self) -> bool {
+        self.rule.is_some() && self.premises.iter().all(|p| p.is_valid())
+    }
+
+    /// from the first leaf that is not an axiom: the atoms on the left are true,
+    /// the other ones are false
+    pub fn counter_model(&self) -> Option<Model> {
+        if self.rule.is_none() {
+            let mut model = Model::new();
+            for f in self.sequent.right.iter().chain(&self.sequent.left) {
+                for s in f.symbols() {
+                    model.set(s, false);
+                }
+            }
+            for f in &self.sequent.left {
+                if let Formula::Leaf(leaf) = f {
+                    model.set(leaf.symbol(), true);
+                }
+            }
+            return Some(model);
+        }
+        self.premises.iter().find_map(|p| p.counter_model())
+    }
+
+    /// the lines of the tree, see `Display`
+    pub fn lines(&self) -> Vec<String> {
+        self.to_string().lines().map(|l| l.to_string()).collect()
+    }
+
+    /// the tree for the `bussproofs` LaTeX package
+    pub fn latex(&self) -> String {
+        let mut out = String::from("\\begin{prooftree}\n");
+        self.write_latex(&mut out);
+        out.push_str("\\end{prooftree}\n");
+        out
+    }
+    fn write_latex(&self, out: &mut String) {
+        for p in &self.premises {
+            p.write_latex(out);
+        }
+        let left = join(&self.sequent.left, latex);
+        let right = join(&self.sequent.right, latex);
+        let sequent = format!("${}$", format!("{left} \\vdash {right}").trim());
+        let inference = match (self.rule, self.premises.len()) {
+            (None, _) => {
+                out.push_str(&format!("\\AxiomC{{{sequent}}}\n"));
+                return;
+            }
+            (Some(_), 0) => {
+                out.push_str("\\AxiomC{}\n");
+                "UnaryInfC"
+            }
+            (Some(_), 1) => "UnaryInfC",
+            (Some(_), _) => "BinaryInfC",
+        };
+        let rule = self.rule.expect("only the leaves have no rule");
+        out.push_str(&format!("\\RightLabel{{${}$}}\n", rule.latex()));
+        out.push_str(&format!("\\{inference}{{{sequent}}}\n"));
+    }
+}
+
+/// `f` in LaTeX, without the outermost parenthesis
+fn latex(f: &Formula) -> String {
+    fn inner(f: &Formula) -> String {
+        match f {
+            Formula::Leaf(leaf) => leaf.symbol().to_string(),
+            Formula::Unary(u) => format!("\\lnot {}", inner(u.right())),
+            Formula::Binary(_) => format!("({})", latex(f)),
+        }
+    }
+    match f {
+        Formula::Binary(b) => {
+            let (l, operator, r) = b.parts();
+            let operator = match operator {
+                Kind::And => "\\land",
+                Kind::Or => "\\lor",
+                Kind::Implies => "\\to",
+                _ => "\\leftrightarrow",
+            };
+            format!("{} {operator} {}", inner(l), inner(r))
+        }
+        _ => inner(f),
+    }
+}
+
+fn write_node(f: &mut fmt::Formatter, node: &Derivation, first: &str, rest: &str) -> fmt::Result {
+    match node.rule {
+        Some(rule) => writeln!(f, "{first}{}  ({})", node.sequent, rule.as_str())?,
+        None => writeln!(f, "{first}{}  (not valid)", node.sequent)?,
+    }
+    for (i, premise) in node.premises.iter().enumerate() {
+        let last = i + 1 == node.premises.len();
+        // `|-` would be read as the turnstile
+        let (first, next) = if last { ("`- ", "   ") } else { ("+- ", "|  ") };
+        write_node(
+            f,
+            premise,
+            &format!("{rest}{first}"),
+            &format!("{rest}{next}"),
+        )?;
+    }
+    Ok(())
+}
+
+/// the conclusion first, then the premises of each rule below it, with the
+/// rule that derives each sequent
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_node(f, self, "", "")
+    }
+}
@@ -0,0 +1,94 @@
+use super::{Derivation, Rule, Sequent};
+use crate::context::Context;
+use crate::parser::Parser;
+use crate::parser::Statement;
+
+fn sequent(buffer: &str) -> Sequent {
+    let mut pars = Parser::new();
+    pars.load_bytes(format!("sequent {buffer}"));
+    match pars.parse_statement(&Context::new()).unwrap() {
+        Statement::Sequent(sequent) => sequent,
+        s => panic!("{s}"),
+    }
+}
+
+#[test]
+fn test_sequent() {
+    let valid = [
+        "a, a => b |- b",
+        "|- a | ~a",
+        "|- (a => b) | (b => a)",
+        "a & b |- b & a",
+        "~(a | b) |- ~a & ~b",
+        "a <=> b |- (a => b) & (b => a)",
+        "a, ~a |-",
+        "a => (b => c) |- (a & b) => c",
+        "|- ((a => b) => a) => a",
+    ];
+    for s in valid {
+        let derivation = Derivation::prove(sequent(s));
+        assert!(derivation.is_valid(), "{s}");
+        assert!(derivation.counter_model().is_none(), "{s}");
+    }
+    let invalid = ["a | b |- a", "|-", "a => b |- b => a", "a |- b, c"];
+    for s in invalid {
+        let derivation = Derivation::prove(sequent(s));
+        assert!(!derivation.is_valid(), "{s}");
+        // the counterexample makes the left side true and the right side false
+        let model = derivation.counter_model().unwrap();
+        let sequent = derivation.sequent();
+        assert!(
+            sequent.left().iter().all(|f| f.eval(&model) == Some(true)),
+            "{s}"
+        );
+        assert!(
+            sequent
+                .right()
+                .iter()
+                .all(|f| f.eval(&model) == Some(false)),
+            "{s}"
+        );
+    }
+}
+
+#[test]
+fn test_sequent_output() {
+    let derivation = Derivation::prove(sequent("a | b |- b | a"));
+    assert_eq!(derivation.rule(), Some(Rule::OrR));
+    assert_eq!(
+        derivation.lines(),
+        [
+            "(a | b) |- (b | a)  (|R)",
+            "`- (a | b) |- b, a  (|L)",
+            "   +- a |- b, a  (Ax)",
+            "   `- b |- b, a  (Ax)",
+        ]
+    );
+    assert_eq!(
+        derivation.latex(),
+        "\
+\\begin{prooftree}
+\\AxiomC{}
+\\RightLabel{$\\mathrm{Ax}$}
+\\UnaryInfC{$a \\vdash b, a$}
+\\AxiomC{}
+\\RightLabel{$\\mathrm{Ax}$}
+\\UnaryInfC{$b \\vdash b, a$}
+\\RightLabel{$\\lor L$}
+\\BinaryInfC{$a \\lor b \\vdash b, a$}
+\\RightLabel{$\\lor R$}
+\\UnaryInfC{$a \\lor b \\vdash b \\lor a$}
+\\end{prooftree}
+"
+    );
+    let derivation = Derivation::prove(sequent("a | b |- a"));
+    assert_eq!(
+        derivation.lines(),
+        [
+            "(a | b) |- a  (|L)",
+            "+- a |- a  (Ax)",
+            "`- b |- a  (not valid)",
+        ]
+    );
+    assert_eq!(derivation.counter_model().unwrap().to_string(), "{~a, b}");
+}
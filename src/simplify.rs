@@ -0,0 +1,300 @@
+use crate::ast::{Formula, Simplified};
+use crate::symbol::Symbol;
+use crate::token::Kind;
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// the rewrite rules of the simplifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// `(a & b) & c` -> `a & b & c`, the same for `|`
+    Flattening,
+    /// `~~a` -> `a`
+    DoubleNegation,
+    /// `a & a` -> `a`, `a | a` -> `a`
+    Idempotence,
+    /// `a & ~a` -> `false`, `a | ~a` -> `true`, `a => a` and `a <=> a` -> `true`,
+    /// `a <=> ~a` -> `false`
+    Complement,
+    /// `a & (a | b)` -> `a`, `a | (a & b)` -> `a`
+    Absorption,
+    /// `true & a` -> `a`, `false & a` -> `false`, `~true` -> `false`, ...
+    ConstantFolding,
+}
+
+impl Rule {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Rule::Flattening => "flattening",
+            Rule::DoubleNegation => "double negation",
+            Rule::Idempotence => "idempotence",
+            Rule::Complement => "complement",
+            Rule::Absorption => "absorption",
+            Rule::ConstantFolding => "constant folding",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// a rule applied to a subformula
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    rule: Rule,
+    before: String,
+    after: String,
+}
+
+impl Step {
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    /// the subformula rewritten
+    pub fn before(&self) -> &str {
+        &self.before
+    }
+    /// what it was rewritten to
+    pub fn after(&self) -> &str {
+        &self.after
+    }
+}
+
+/// e.g. `idempotence: (a & a) -> a`
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.rule, self.before, self.after)
+    }
+}
+
+/// a formula with constants, `&` and `|` with any number of operands
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Constant(bool),
+    Atom(Symbol),
+    Not(Box<Term>),
+    /// `&` or `|`
+    Chain(Kind, Vec<Term>),
+    /// `=>` or `<=>`
+    Binary(Kind, Box<Term>, Box<Term>),
+}
+
+impl Term {
+    fn not(t: Term) -> Term {
+        Term::Not(Box::new(t))
+    }
+    /// the chain, or its only term, or the constant that is its neutral element
+    fn chain(operator: Kind, mut terms: Vec<Term>) -> Term {
+        match terms.len() {
+            0 => Term::Constant(operator == Kind::And),
+            1 => terms.pop().expect("there is a term"),
+            _ => Term::Chain(operator, terms),
+        }
+    }
+    /// true if `self` is `~t`
+    fn is_not(&self, t: &Term) -> bool {
+        matches!(self, Term::Not(x) if x.as_ref() == t)
+    }
+
+    /// the rule that applies at the top of the term, and its result
+    fn rewrite(&self) -> Option<(Rule, Term)> {
+        match self {
+            Term::Constant(_) | Term::Atom(_) => None,
+            Term::Not(x) => match x.as_ref() {
+                Term::Constant(value) => Some((Rule::ConstantFolding, Term::Constant(!value))),
+                Term::Not(y) => Some((Rule::DoubleNegation, y.as_ref().clone())),
+                _ => None,
+            },
+            Term::Chain(operator, terms) => Term::rewrite_chain(*operator, terms),
+            Term::Binary(operator, l, r) => {
+                let (l, r) = (l.as_ref(), r.as_ref());
+                let equiv = *operator == Kind::Equiv;
+                if l == r {
+                    return Some((Rule::Complement, Term::Constant(true)));
+                }
+                if equiv && (l.is_not(r) || r.is_not(l)) {
+                    return Some((Rule::Complement, Term::Constant(false)));
+                }
+                let folded = match (l, r) {
+                    (Term::Constant(true), _) => r.clone(),
+                    (Term::Constant(false), _) if equiv => Term::not(r.clone()),
+                    (Term::Constant(false), _) => Term::Constant(true),
+                    (_, Term::Constant(true)) if equiv => l.clone(),
+                    (_, Term::Constant(true)) => Term::Constant(true),
+                    (_, Term::Constant(false)) => Term::not(l.clone()),
+                    _ => return None,
+                };
+                Some((Rule::ConstantFolding, folded))
+            }
+        }
+    }
+
+    fn rewrite_chain(operator: Kind, terms: &[Term]) -> Option<(Rule, Term)> {
+        let is_chain = |t: &Term, kind: Kind| matches!(t, Term::Chain(k, _) if *k == kind);
+        // the value of the chain if one of the terms has it
+        let absorbing = operator == Kind::Or;
+        if terms.iter().any(|t| is_chain(t, operator)) {
+            let mut flat = Vec::new();
+            for t in terms {
+                match t {
+                    Term::Chain(k, inner) if *k == operator => flat.extend(inner.iter().cloned()),
+                    _ => flat.push(t.clone()),
+                }
+            }
+            return Some((Rule::Flattening, Term::chain(operator, flat)));
+        }
+        if terms.contains(&Term::Constant(absorbing)) {
+            return Some((Rule::ConstantFolding, Term::Constant(absorbing)));
+        }
+        if terms.contains(&Term::Constant(!absorbing)) {
+            let rest = terms.iter().filter(|t| !matches!(t, Term::Constant(_)));
+            return Some((
+                Rule::ConstantFolding,
+                Term::chain(operator, rest.cloned().collect()),
+            ));
+        }
+        let mut unique: Vec<Term> = Vec::with_capacity(terms.len());
+        for t in terms {
+            if !unique.contains(t) {
+                unique.push(t.clone());
+            }
+        }
+        if unique.len() < terms.len() {
+            return Some((Rule::Idempotence, Term::chain(operator, unique)));
+        }
+        if terms.iter().any(|t| terms.iter().any(|u| u.is_not(t))) {
+            return Some((Rule::Complement, Term::Constant(absorbing)));
+        }
+        let dual = if operator == Kind::And {
+            Kind::Or
+        } else {
+            Kind::And
+        };
+        // a chain of the other operator with one of the terms
+        let absorbed = |t: &Term| match t {
+            Term::Chain(k, inner) if *k == dual => inner.iter().any(|x| terms.contains(x)),
+            _ => false,
+        };
+        if terms.iter().any(absorbed) {
+            let rest = terms.iter().filter(|t| !absorbed(t)).cloned().collect();
+            return Some((Rule::Absorption, Term::chain(operator, rest)));
+        }
+        None
+    }
+
+    /// None for a constant, which can only be the whole term once simplified
+    fn to_formula(&self) -> Option<Formula> {
+        Some(match self {
+            Term::Constant(_) => return None,
+            Term::Atom(s) => Formula::new_leaf(*s),
+            Term::Not(x) => Formula::new_unary(Kind::Not, x.to_formula()?),
+            Term::Chain(operator, terms) => {
+                let mut formulas = terms.iter().map(|t| t.to_formula());
+                let first = formulas.next().expect("a chain has 2 terms")?;
+                formulas.try_fold(first, |l, r| Some(Formula::new_binary(l, *operator, r?)))?
+            }
+            Term::Binary(operator, l, r) => {
+                Formula::new_binary(l.to_formula()?, *operator, r.to_formula()?)
+            }
+        })
+    }
+}
+
+impl From<&Formula> for Term {
+    fn from(f: &Formula) -> Term {
+        match f {
+            Formula::Leaf(leaf) => Term::Atom(leaf.symbol()),
+            Formula::Unary(unary) => Term::not(unary.right().into()),
+            Formula::Binary(binary) => {
+                let (l, operator, r) = binary.parts();
+                match operator {
+                    Kind::And | Kind::Or => Term::Chain(operator, vec![l.into(), r.into()]),
+                    _ => Term::Binary(operator, Box::new(l.into()), Box::new(r.into())),
+                }
+            }
+        }
+    }
+}
+
+/// like `Formula`, with `true` and `false`; `&` and `|` between all the terms
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Term::Constant(value) => write!(f, "{value}"),
+            Term::Atom(s) => write!(f, "{s}"),
+            Term::Not(x) => write!(f, "(~{x})"),
+            Term::Chain(operator, terms) => {
+                let terms: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+                write!(f, "({})", terms.join(&format!(" {operator} ")))
+            }
+            Term::Binary(operator, l, r) => write!(f, "({l} {operator} {r})"),
+        }
+    }
+}
+
+/// a formula simplified by rewriting, from the atoms up, with the steps done
+#[derive(Debug, Clone)]
+pub struct Simplification {
+    result: Simplified,
+    steps: Vec<Step>,
+}
+
+impl Simplification {
+    pub fn new(formula: &Formula) -> Simplification {
+        let mut steps = Vec::new();
+        let term = simplify(Term::from(formula), &mut steps);
+        let result = match term.to_formula() {
+            Some(formula) => Simplified::Formula(formula),
+            None => match term {
+                Term::Constant(value) => Simplified::Constant(value),
+                _ => unreachable!("a constant is only left alone"),
+            },
+        };
+        Simplification { result, steps }
+    }
+    pub fn result(&self) -> &Simplified {
+        &self.result
+    }
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+}
+
+/// simplifies the subterms, then the term itself
+fn simplify(t: Term, steps: &mut Vec<Step>) -> Term {
+    let t = match t {
+        Term::Not(x) => Term::not(simplify(*x, steps)),
+        Term::Chain(operator, terms) => Term::Chain(
+            operator,
+            terms.into_iter().map(|t| simplify(t, steps)).collect(),
+        ),
+        Term::Binary(operator, l, r) => Term::Binary(
+            operator,
+            Box::new(simplify(*l, steps)),
+            Box::new(simplify(*r, steps)),
+        ),
+        Term::Constant(_) | Term::Atom(_) => t,
+    };
+    rewrite(t, steps)
+}
+
+/// applies the rules at the top of `t` until none does, its subterms are
+/// already simplified and so are the ones of each result
+fn rewrite(t: Term, steps: &mut Vec<Step>) -> Term {
+    match t.rewrite() {
+        Some((rule, after)) => {
+            steps.push(Step {
+                rule,
+                before: t.to_string(),
+                after: after.to_string(),
+            });
+            rewrite(after, steps)
+        }
+        None => t,
+    }
+}
@@ -0,0 +1,116 @@
+use super::{Rule, Simplification};
+use crate::ast::Simplified;
+use crate::context::Context;
+use crate::model::Model;
+use crate::testutil::formulas;
+use std::sync::Arc;
+
+#[test]
+fn test_simplify() {
+    let tests: &[(&str, &str, &[Rule])] = &[
+        ("a & a", "a", &[Rule::Idempotence]),
+        ("a | ~a", "true", &[Rule::Complement]),
+        ("~~a", "a", &[Rule::DoubleNegation]),
+        (
+            "(a => a) & b",
+            "b",
+            &[Rule::Complement, Rule::ConstantFolding],
+        ),
+        ("a & (a | b)", "a", &[Rule::Absorption]),
+        (
+            "(a | b) | (c | d)",
+            "(((a | b) | c) | d)",
+            &[Rule::Flattening],
+        ),
+        ("a <=> ~a", "false", &[Rule::Complement]),
+        (
+            "(a & ~a) => b",
+            "true",
+            &[Rule::Complement, Rule::ConstantFolding],
+        ),
+        (
+            "b => (a & ~a)",
+            "(~b)",
+            &[Rule::Complement, Rule::ConstantFolding],
+        ),
+        ("a & b => c", "((a & b) => c)", &[]),
+    ];
+    for (input, expected, rules) in tests {
+        let simplification = Simplification::new(&formulas(input)[0]);
+        assert_eq!(simplification.result().to_string(), *expected, "{input}");
+        let applied: Vec<Rule> = simplification.steps().iter().map(|s| s.rule()).collect();
+        assert_eq!(applied, *rules, "{input}");
+    }
+}
+
+#[test]
+fn test_simplify_steps() {
+    let simplification = Simplification::new(&formulas("(a & b) & (c & a) | ~~(a & ~a)")[0]);
+    let steps: Vec<String> = simplification
+        .steps()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            "flattening: ((a & b) & (c & a)) -> (a & b & c & a)",
+            "idempotence: (a & b & c & a) -> (a & b & c)",
+            "complement: (a & (~a)) -> false",
+            "constant folding: (~false) -> true",
+            "constant folding: (~true) -> false",
+            "constant folding: ((a & b & c) | false) -> (a & b & c)",
+        ]
+    );
+    assert_eq!(simplification.result().to_string(), "((a & b) & c)");
+}
+
+/// the result has the same value as the formula in every assignment
+#[test]
+fn test_simplify_equivalent() {
+    let buffer = "
+(a | b) & (a | ~b) & (c | a)
+~(a & ~a) | (b <=> b)
+(a => (b => a)) & (~~c | c)
+(a <=> b) & (a & (b | c) | ~~a)
+a & (b | (c & a)) & ~(b & ~b)
+(a | b) & ~(a | b)
+";
+    for f in formulas(buffer) {
+        let symbols = f.symbols();
+        let simplification = Simplification::new(&f);
+        for n in 0..1 << symbols.len() {
+            let mut model = Model::new();
+            for (i, &s) in symbols.iter().enumerate() {
+                model.set(s, n >> i & 1 == 1);
+            }
+            let value = match simplification.result() {
+                Simplified::Constant(value) => Some(*value),
+                Simplified::Formula(simplified) => simplified.eval(&model),
+            };
+            assert_eq!(value, f.eval(&model), "{f}");
+        }
+    }
+}
+
+/// the formulas are simplified before the conversion to clauses
+#[test]
+fn test_simplify_clauses() {
+    let f = Arc::new(formulas("(a & a) | (a & b)")[0].clone());
+    let mut context = Context::new();
+    context.push(Arc::clone(&f)).unwrap();
+    assert_eq!(context.inner()[0].set_clauses().to_string(), "{{a}}");
+    context.set_simplify(false);
+    context.push(f).unwrap();
+    assert_eq!(
+        context.inner()[1].set_clauses().to_string(),
+        "{{a}, {a, b}}"
+    );
+
+    let mut context = Context::new();
+    context
+        .push(Arc::new(formulas("a & ~a")[0].clone()))
+        .unwrap();
+    assert_eq!(context.inner()[0].set_clauses().to_string(), "{{}}");
+    assert!(context.solve());
+}
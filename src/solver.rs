@@ -0,0 +1,342 @@
+use crate::clause::{Clause, ClauseId, Lit, Restriction, SetClauses};
+use crate::fragment::Fragment;
+use crate::model::Model;
+use crate::order::Ranking;
+use crate::preprocess::Preprocessor;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod test;
+
+/// how `!` looks for a box
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Method {
+    #[default]
+    Resolution,
+    /// see `Restriction::Ordered`
+    Ordered,
+    /// see `Restriction::Positive`
+    Positive,
+    /// see `Restriction::Negative`
+    Negative,
+    /// see `Restriction::Hyper`
+    Hyper,
+    /// see `Restriction::Linear`
+    Linear,
+    /// see `Restriction::Input`
+    Input,
+}
+
+impl Method {
+    pub const ALL: [Method; 7] = [
+        Method::Resolution,
+        Method::Ordered,
+        Method::Positive,
+        Method::Negative,
+        Method::Hyper,
+        Method::Linear,
+        Method::Input,
+    ];
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Resolution => "resolution",
+            Method::Ordered => "ordered",
+            Method::Positive => "positive",
+            Method::Negative => "negative",
+            Method::Hyper => "hyper",
+            Method::Linear => "linear",
+            Method::Input => "input",
+        }
+    }
+    /// the names of all the methods, e.g. "`resolution`, `ordered`"
+    pub fn names() -> String {
+        Method::ALL
+            .iter()
+            .map(|m| format!("`{m}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl FromStr for Method {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Method, String> {
+        Method::ALL
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| format!("unknown solver `{s}` (expected one of {})", Method::names()))
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// identifies a group of input clauses that can be removed together
+pub type Tag = u32;
+
+/// a resolution solver that keeps what it has derived between calls: new
+/// clauses are resolved only with each other and with the ones already there
+#[derive(Debug, Clone)]
+pub struct Solver {
+    clauses: SetClauses,
+    /// the groups of input clauses added with `add_tagged()`
+    inputs: Vec<(Tag, SetClauses)>,
+    /// the group each input clause was first added with
+    tags: HashMap<ClauseId, Tag>,
+    jobs: usize,
+    preprocess: bool,
+    propagate: bool,
+    fragments: bool,
+    fragment: Fragment,
+    restriction: Restriction,
+    ranking: Option<Ranking>,
+    complete: bool,
+    preprocessor: Preprocessor,
+    proof: Vec<String>,
+    model: Option<Model>,
+}
+
+impl Default for Solver {
+    fn default() -> Solver {
+        Solver {
+            clauses: SetClauses::default(),
+            inputs: Vec::new(),
+            tags: HashMap::new(),
+            jobs: 1,
+            preprocess: false,
+            propagate: false,
+            fragments: true,
+            fragment: Fragment::General,
+            restriction: Restriction::None,
+            ranking: None,
+            complete: true,
+            preprocessor: Preprocessor::new(),
+            proof: Vec::new(),
+            model: None,
+        }
+    }
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver::default()
+    }
+    /// the number of threads used by each call, see `SetClauses::find_box_jobs()`
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs.max(1);
+    }
+    /// eliminates symbols before resolving, see `Preprocessor`
+    pub fn set_preprocess(&mut self, preprocess: bool) {
+        self.preprocess = preprocess;
+    }
+
+    /// propagates the unit clauses before each round, see `SetClauses::propagate()`
+    pub fn set_propagate(&mut self, propagate: bool) {
+        self.propagate = propagate;
+    }
+
+    /// uses the linear-time algorithms of the Horn and 2-CNF clause sets,
+    /// see `Fragment`
+    pub fn set_fragments(&mut self, fragments: bool) {
+        self.fragments = fragments;
+    }
+
+    /// the pairs of clauses resolved, see `Restriction`
+    pub fn set_restriction(&mut self, restriction: Restriction) {
+        if restriction != self.restriction {
+            // the pairs skipped before could be resolved now
+            self.clauses.set_resolved(0);
+            self.restriction = restriction;
+        }
+    }
+
+    pub fn add(&mut self, clauses: &SetClauses) {
+        self.check_preprocessing(clauses.ids().flat_map(|id| clauses.clause(id)));
+        self.clauses.extend(clauses);
+    }
+    pub fn add_clause(&mut self, clause: &Clause) -> ClauseId {
+        self.check_preprocessing(clause.lits().iter());
+        self.clauses.insert(clause)
+    }
+    /// like `add()`, but the clauses can be removed with `remove_tagged()`
+    pub fn add_tagged(&mut self, tag: Tag, clauses: &SetClauses) {
+        self.check_preprocessing(clauses.ids().flat_map(|id| clauses.clause(id)));
+        self.insert_tagged(tag, clauses);
+        self.inputs.push((tag, clauses.clone()));
+    }
+    fn insert_tagged(&mut self, tag: Tag, clauses: &SetClauses) {
+        for id in clauses.ids() {
+            let len = self.clauses.len();
+            let id = self
+                .clauses
+                .insert(&Clause::from(clauses.clause(id).to_vec()));
+            if id as usize == len {
+                self.tags.insert(id, tag);
+            }
+        }
+    }
+    pub fn has_tagged(&self, tag: Tag) -> bool {
+        self.inputs.iter().any(|(t, _)| *t == tag)
+    }
+
+    /// the clauses eliminated are needed again if a symbol eliminated comes back
+    fn check_preprocessing<'a>(&mut self, lits: impl Iterator<Item = &'a Lit>) {
+        let lits: Vec<Lit> = lits.copied().collect();
+        if self.preprocessor.is_affected_by(&lits) {
+            self.undo_preprocessing();
+        }
+    }
+    /// returns false if nothing was eliminated
+    fn undo_preprocessing(&mut self) -> bool {
+        self.preprocessor = Preprocessor::new();
+        self.clauses.restore()
+    }
+
+    /// removes the clauses added with `tag` and the ones derived from them:
+    /// everything else that was derived is kept
+    pub fn remove_tagged(&mut self, tag: Tag) {
+        self.undo_preprocessing();
+        self.inputs.retain(|(t, _)| *t != tag);
+        let roots: Vec<ClauseId> = self
+            .tags
+            .iter()
+            .filter(|&(_, t)| *t == tag)
+            .map(|(&id, _)| id)
+            .collect();
+        self.remove_derived(&roots);
+        // a clause could also be in a group still there
+        for (tag, clauses) in std::mem::take(&mut self.inputs) {
+            self.insert_tagged(tag, &clauses);
+            self.inputs.push((tag, clauses));
+        }
+    }
+
+    /// removes `roots` and the clauses derived from them
+    fn remove_derived(&mut self, roots: &[ClauseId]) {
+        if roots.is_empty() {
+            return;
+        }
+        let mut depends = vec![false; self.clauses.len()];
+        for id in self.clauses.ids() {
+            depends[id as usize] = roots.contains(&id)
+                || self
+                    .clauses
+                    .premises(id)
+                    .iter()
+                    .any(|&c| depends[c as usize]);
+        }
+        let ids = self.clauses.retain_ids(|_, id| !depends[id as usize]);
+        self.tags = std::mem::take(&mut self.tags)
+            .into_iter()
+            .filter_map(|(id, tag)| Some((ids[id as usize]?, tag)))
+            .collect();
+    }
+
+    /// the input clauses and the ones derived so far
+    pub fn clauses(&self) -> &SetClauses {
+        &self.clauses
+    }
+    /// the proof found by the last call, empty if there was no box
+    pub fn proof(&self) -> &[String] {
+        &self.proof
+    }
+    /// the fragment recognised by the last call, `General` if it was solved
+    /// by resolution
+    pub fn fragment(&self) -> Fragment {
+        self.fragment
+    }
+    /// the ordering of the atoms used by the last call, with ordered resolution
+    pub fn ranking(&self) -> Option<&Ranking> {
+        self.ranking.as_ref()
+    }
+    /// false if the restriction of the last call missed the box: it was not
+    /// found but the clauses have no model, see `Restriction::is_complete_for()`
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+    /// a model of the clauses (and of the assumptions) of the last call,
+    /// None if the box was found (or missed, see `is_complete()`)
+    pub fn model(&self) -> Option<&Model> {
+        self.model.as_ref()
+    }
+
+    /// returns true if the box is found
+    pub fn solve(&mut self) -> bool {
+        self.solve_with(&[])
+    }
+
+    /// like `solve()`, but the `assumptions` are true only for this call:
+    /// afterwards the clauses derived from them are removed
+    pub fn solve_with(&mut self, assumptions: &[Lit]) -> bool {
+        self.check_preprocessing(assumptions.iter());
+        let first = self.clauses.len() as ClauseId;
+        let resolved = self.clauses.resolved();
+        for &lit in assumptions {
+            self.clauses.insert(&Clause::from(vec![lit]));
+        }
+        self.fragment = Fragment::General;
+        self.complete = true;
+        self.ranking = match &self.restriction {
+            Restriction::Ordered(order) => Some(order.ranking(self.clauses.active_symbols())),
+            _ => None,
+        };
+        let found = if self.preprocess && self.preprocessor.run(&mut self.clauses) {
+            self.model = None;
+            true
+        } else {
+            if self.fragments && self.restriction == Restriction::None {
+                self.fragment = Fragment::of(&self.clauses);
+            }
+            match self.fragment.solve(&mut self.clauses) {
+                Some(model) => {
+                    self.model = model;
+                    self.model.is_none()
+                }
+                None if self
+                    .clauses
+                    .saturate(self.jobs, self.propagate, &self.restriction) =>
+                {
+                    self.model = None;
+                    true
+                }
+                None => {
+                    // None if an incomplete restriction missed the box
+                    self.model = match (&self.restriction, &self.ranking) {
+                        (_, Some(ranking)) => Some(self.clauses.model_ordered(ranking)),
+                        (Restriction::None, None) => Some(self.clauses.model()),
+                        _ => self.clauses.model_search(),
+                    };
+                    // without a model the box was missed, e.g. by input resolution
+                    // for clauses that are not Horn
+                    self.complete = self.model.is_some();
+                    false
+                }
+            }
+        };
+        if let Some(model) = &mut self.model {
+            self.preprocessor.extend_model(model);
+        }
+        self.proof = self.clauses.trace_from_box();
+        // an assumption already in the set is not temporary
+        let temporary: Vec<ClauseId> = assumptions
+            .iter()
+            .filter_map(|&lit| self.clauses.find(&[lit]))
+            .filter(|&id| id >= first)
+            .collect();
+        if !temporary.is_empty() {
+            // what was eliminated could depend on the assumptions
+            let restored = self.undo_preprocessing();
+            self.remove_derived(&temporary);
+            if !restored {
+                // the clauses before `first` are still there, with the same ids
+                self.clauses.set_resolved(resolved);
+            }
+        }
+        found
+    }
+}
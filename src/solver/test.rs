@@ -0,0 +1,169 @@
+use super::{Method, Solver};
+use crate::clause::{Clause, Lit, Restriction, SetClauses};
+use crate::symbol::Symbol;
+use crate::testutil::{random_set, Random};
+
+fn clause(lits: &[(&str, bool)]) -> Clause {
+    lits.iter()
+        .map(|&(name, positive)| {
+            let s = Symbol::intern(name);
+            if positive {
+                Lit::positive(s)
+            } else {
+                Lit::negative(s)
+            }
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[test]
+fn test_method() {
+    assert_eq!("resolution".parse(), Ok(Method::Resolution));
+    assert_eq!(Method::Resolution.to_string(), "resolution");
+    assert!("x".parse::<Method>().is_err());
+    for method in Method::ALL {
+        assert_eq!(method.as_str().parse(), Ok(method));
+    }
+}
+
+/// positive, negative and hyper-resolution find the box exactly when resolution
+/// does, and their models are models
+#[test]
+fn test_restrictions_random() {
+    let mut random = Random::new(5);
+    let restrictions = [
+        Restriction::Positive,
+        Restriction::Negative,
+        Restriction::Hyper,
+    ];
+    for round in 0..150 {
+        let input = random_set(&mut random, "h");
+        let unsat = input.clone().find_box();
+        let mut solver = Solver::new();
+        solver.set_restriction(restrictions[round % 3].clone());
+        solver.set_propagate(round % 2 == 0);
+        solver.add(&input);
+        assert_eq!(solver.solve(), unsat, "{input}");
+        if let Some(model) = solver.model() {
+            for id in input.ids() {
+                assert!(model.satisfies(input.clause(id)), "{input}: {model}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_incremental() {
+    let mut solver = Solver::new();
+    // resolution, not the algorithms of the fragments
+    solver.set_fragments(false);
+    solver.add_clause(&clause(&[("a", true), ("b", true)]));
+    solver.add_clause(&clause(&[("a", false), ("c", true)]));
+    assert!(!solver.solve());
+    assert!(solver.proof().is_empty());
+    let derived = solver.clauses().len();
+    assert!(derived > 2);
+
+    // the clauses derived before are kept
+    solver.add_clause(&clause(&[("b", false)]));
+    assert!(!solver.solve());
+    assert!(solver.clauses().len() > derived);
+    assert!(solver.clauses().contains(clause(&[("c", true)]).lits()));
+
+    let mut set = SetClauses::default();
+    set.insert(&clause(&[("c", false)]));
+    solver.add(&set);
+    assert!(solver.solve());
+    assert!(!solver.proof().is_empty());
+    // the box stays found
+    assert!(solver.solve());
+}
+
+#[test]
+fn test_assumptions() {
+    let (a, b) = (Symbol::intern("a"), Symbol::intern("b"));
+    let mut solver = Solver::new();
+    solver.set_jobs(2);
+    solver.add_clause(&clause(&[("a", false), ("b", true)]));
+    assert!(!solver.solve());
+    let len = solver.clauses().len();
+
+    assert!(solver.solve_with(&[Lit::positive(a), Lit::negative(b)]));
+    assert_eq!(solver.proof().last().unwrap(), "{b}, {~b} -> {}");
+    // the assumptions and what was derived from them are gone
+    assert_eq!(solver.clauses().len(), len);
+    assert!(!solver.clauses().contains(&[Lit::positive(a)]));
+    assert!(!solver.solve());
+    assert!(solver.proof().is_empty());
+
+    assert!(!solver.solve_with(&[Lit::positive(a)]));
+    // an assumption that is already a clause stays
+    solver.add_clause(&clause(&[("a", true)]));
+    assert!(solver.solve_with(&[Lit::positive(a), Lit::negative(b)]));
+    assert!(solver.clauses().contains(&[Lit::positive(a)]));
+    assert!(solver.clauses().contains(&[Lit::positive(b)]));
+    assert!(!solver.clauses().contains(&[]));
+}
+
+#[test]
+fn test_remove_tagged() {
+    let set = |clauses: &[Clause]| {
+        let mut set = SetClauses::default();
+        for c in clauses {
+            set.insert(c);
+        }
+        set
+    };
+    let mut solver = Solver::new();
+    // resolution, not the algorithms of the fragments
+    solver.set_fragments(false);
+    solver.add_tagged(0, &set(&[clause(&[("a", true), ("b", true)])]));
+    solver.add_tagged(1, &set(&[clause(&[("a", false), ("c", true)])]));
+    solver.add_tagged(2, &set(&[clause(&[("b", false), ("d", true)])]));
+    solver.add_tagged(3, &set(&[clause(&[("a", false), ("c", true)])]));
+    assert!(!solver.solve());
+    assert!(solver
+        .clauses()
+        .contains(clause(&[("c", true), ("d", true)]).lits()));
+
+    solver.remove_tagged(2);
+    assert!(!solver.has_tagged(2));
+    // {b, c} does not depend on the formula removed
+    assert!(solver
+        .clauses()
+        .contains(clause(&[("b", true), ("c", true)]).lits()));
+    assert!(!solver
+        .clauses()
+        .contains(clause(&[("c", true), ("d", true)]).lits()));
+    assert!(!solver
+        .clauses()
+        .contains(clause(&[("b", false), ("d", true)]).lits()));
+
+    // {~a, c} is still in the group 3
+    solver.remove_tagged(1);
+    assert!(solver
+        .clauses()
+        .contains(clause(&[("a", false), ("c", true)]).lits()));
+    solver.add_tagged(4, &set(&[clause(&[("c", false)]), clause(&[("b", false)])]));
+    assert!(solver.solve());
+    solver.remove_tagged(4);
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_preprocess_incremental() {
+    let mut solver = Solver::new();
+    solver.set_preprocess(true);
+    solver.add_clause(&clause(&[("a", true), ("b", true)]));
+    solver.add_clause(&clause(&[("a", false), ("b", true)]));
+    assert!(!solver.solve());
+    assert_eq!(
+        solver.model().unwrap().value(Symbol::intern("b")),
+        Some(true)
+    );
+    // `b` was eliminated as a pure literal
+    solver.add_clause(&clause(&[("b", false)]));
+    assert!(solver.solve());
+    assert!(solver.model().is_none());
+}
@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[cfg(test)]
+mod test;
+
+/// an interned identifier: a compact id in the global `SymbolTable`,
+/// so two symbols are equal only if they have the same name
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn intern(name: &str) -> Symbol {
+        SymbolTable::global().intern(name)
+    }
+    pub fn name(&self) -> Arc<str> {
+        SymbolTable::global().name(*self)
+    }
+    /// ids are given in order of interning, starting from 0
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+    /// `id` must come from `Symbol::id()`
+    pub(crate) fn from_id(id: u32) -> Symbol {
+        Symbol(id)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+/// symbols are sorted by name, so the order does not depend on when they are
+/// interned; the names are read without locking the table
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.0 == other.0 {
+            Ordering::Equal
+        } else {
+            let table = SymbolTable::global();
+            table.name_ref(*self).cmp(table.name_ref(*other))
+        }
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// chunk `k` of the names holds the ids from `2^k - 1`, `2^k` of them: enough
+/// chunks for every `u32`
+const CHUNKS: usize = 33;
+
+/// `2^k` names, each one set once
+type Chunk = Box<[OnceLock<Arc<str>>]>;
+
+/// the chunk of `id` and its index in the chunk
+fn slot(id: u32) -> (usize, usize) {
+    let n = id as u64 + 1;
+    let chunk = 63 - n.leading_zeros() as usize;
+    (chunk, (n - (1 << chunk)) as usize)
+}
+
+/// the identifiers seen so far, shared by the lexer and by the formulas built
+/// programmatically: there is only one table, see `SymbolTable::global()`
+#[derive(Debug)]
+pub struct SymbolTable {
+    /// the symbol of each name, it is not needed to read a name
+    ids: RwLock<HashMap<Arc<str>, Symbol>>,
+    /// the names by id: a chunk is never moved once allocated, so a name can be
+    /// read without the lock (e.g. to compare two symbols)
+    names: [OnceLock<Chunk>; CHUNKS],
+}
+
+impl SymbolTable {
+    pub fn global() -> &'static SymbolTable {
+        static GLOBAL: OnceLock<SymbolTable> = OnceLock::new();
+        GLOBAL.get_or_init(|| SymbolTable {
+            ids: RwLock::new(HashMap::new()),
+            names: std::array::from_fn(|_| OnceLock::new()),
+        })
+    }
+
+    pub fn intern(&self, name: &str) -> Symbol {
+        if let Some(symbol) = self.get(name) {
+            return symbol;
+        }
+        let mut ids = self.ids.write().expect("poisoned symbol table");
+        // someone else could have interned it in the meantime
+        if let Some(symbol) = ids.get(name) {
+            return *symbol;
+        }
+        let id = u32::try_from(ids.len()).expect("too many symbols");
+        let name: Arc<str> = name.into();
+        let (chunk, index) = slot(id);
+        let chunk = self.names[chunk]
+            .get_or_init(|| (0..1usize << chunk).map(|_| OnceLock::new()).collect());
+        chunk[index]
+            .set(Arc::clone(&name))
+            .expect("an id is given only once");
+        ids.insert(name, Symbol(id));
+        Symbol(id)
+    }
+
+    /// the symbol of `name` only if it has already been interned
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        let ids = self.ids.read().expect("poisoned symbol table");
+        ids.get(name).copied()
+    }
+
+    pub fn name(&self, symbol: Symbol) -> Arc<str> {
+        Arc::clone(self.name_ref(symbol))
+    }
+
+    fn name_ref(&self, symbol: Symbol) -> &Arc<str> {
+        let (chunk, index) = slot(symbol.0);
+        self.names[chunk]
+            .get()
+            .and_then(|chunk| chunk[index].get())
+            .expect("a symbol is interned before it is used")
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.read().expect("poisoned symbol table").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
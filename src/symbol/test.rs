@@ -0,0 +1,48 @@
+use super::{slot, Symbol, SymbolTable};
+use std::sync::Arc;
+
+#[test]
+fn test_intern() {
+    let a = Symbol::intern("symbol_test_a");
+    let b = SymbolTable::global().intern("symbol_test_b");
+    assert_eq!(a, Symbol::from("symbol_test_a"));
+    assert_ne!(a, b);
+    assert_eq!(SymbolTable::global().get("symbol_test_a"), Some(a));
+    assert_eq!(
+        SymbolTable::global().get("symbol_test_never_interned"),
+        None
+    );
+    assert_eq!(&*a.name(), "symbol_test_a");
+    assert_eq!(b.to_string(), "symbol_test_b");
+    // the names are stored only once
+    assert!(Arc::ptr_eq(&a.name(), &SymbolTable::global().name(a)));
+}
+
+#[test]
+fn test_order() {
+    // sorted by name, not by id
+    let z = Symbol::intern("symbol_test_z");
+    let y = Symbol::intern("symbol_test_y");
+    assert!(z.id() < y.id());
+    assert!(y < z);
+}
+
+#[test]
+fn test_slot() {
+    assert_eq!(slot(0), (0, 0));
+    assert_eq!(slot(1), (1, 0));
+    assert_eq!(slot(2), (1, 1));
+    assert_eq!(slot(3), (2, 0));
+    assert_eq!(slot(6), (2, 3));
+    assert_eq!(slot(7), (3, 0));
+    assert_eq!(slot(u32::MAX), (32, 0));
+}
+
+#[test]
+fn test_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| Symbol::intern("symbol_test_thread")))
+        .collect();
+    let symbols: Vec<Symbol> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert!(symbols.windows(2).all(|w| w[0] == w[1]));
+}
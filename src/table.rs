@@ -0,0 +1,150 @@
+use crate::ast::Formula;
+use crate::model::Model;
+use crate::symbol::Symbol;
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// the default of the most atoms of a table, which has 2^atoms rows
+pub const LIMIT: usize = 10;
+
+/// the most atoms of any table (also with a greater limit): 2^20 rows
+pub const MAX_LIMIT: usize = 20;
+
+/// the formula has more atoms than the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLarge {
+    pub atoms: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} atoms, more than the limit of {}",
+            self.atoms, self.limit
+        )
+    }
+}
+
+/// the truth table of a formula: a column for each atom, then one for each
+/// subformula, the formula last
+#[derive(Debug, Clone)]
+pub struct TruthTable {
+    atoms: Vec<Symbol>,
+    /// the subformulas that are not atoms, each one after its own subformulas
+    columns: Vec<Formula>,
+    /// the values of the atoms then of the columns, counting in binary from
+    /// all false (the first atom is the most significant)
+    rows: Vec<Vec<bool>>,
+}
+
+impl TruthTable {
+    /// the atoms in the order they appear in `formula`, at most `limit` (and
+    /// at most `MAX_LIMIT`)
+    pub fn new(formula: &Formula, limit: usize) -> Result<TruthTable, TooLarge> {
+        let limit = limit.min(MAX_LIMIT);
+        let atoms = formula.symbols();
+        if atoms.len() > limit {
+            return Err(TooLarge {
+                atoms: atoms.len(),
+                limit,
+            });
+        }
+        let mut columns = Vec::new();
+        subformulas(formula, &mut columns);
+        let mut rows = Vec::with_capacity(1 << atoms.len());
+        for n in 0..1usize << atoms.len() {
+            let mut model = Model::new();
+            let mut row = Vec::with_capacity(atoms.len() + columns.len());
+            for (i, &atom) in atoms.iter().enumerate() {
+                let value = n >> (atoms.len() - 1 - i) & 1 == 1;
+                model.set(atom, value);
+                row.push(value);
+            }
+            for column in &columns {
+                row.push(column.eval(&model).expect("every atom is assigned"));
+            }
+            rows.push(row);
+        }
+        Ok(TruthTable {
+            atoms,
+            columns,
+            rows,
+        })
+    }
+    pub fn atoms(&self) -> &[Symbol] {
+        &self.atoms
+    }
+    pub fn columns(&self) -> &[Formula] {
+        &self.columns
+    }
+    /// the headers of the atoms then of the columns
+    pub fn headers(&self) -> Vec<String> {
+        let atoms = self.atoms.iter().map(|a| a.to_string());
+        atoms
+            .chain(self.columns.iter().map(|c| c.to_string()))
+            .collect()
+    }
+    pub fn rows(&self) -> &[Vec<bool>] {
+        &self.rows
+    }
+    /// the value of the formula in a row
+    pub fn value(&self, row: usize) -> bool {
+        *self.rows[row].last().expect("a row is never empty")
+    }
+    /// the rows where the formula is true
+    pub fn true_rows(&self) -> Vec<usize> {
+        (0..self.rows.len()).filter(|&r| self.value(r)).collect()
+    }
+    /// the lines of the table, see `Display`
+    pub fn lines(&self) -> Vec<String> {
+        self.to_string().lines().map(|l| l.to_string()).collect()
+    }
+}
+
+/// adds the subformulas of `f` that are not atoms, each one once and after
+/// its own subformulas
+fn subformulas(f: &Formula, columns: &mut Vec<Formula>) {
+    match f {
+        Formula::Leaf(_) => return,
+        Formula::Unary(unary) => subformulas(unary.right(), columns),
+        Formula::Binary(binary) => {
+            let (l, _, r) = binary.parts();
+            subformulas(l, columns);
+            subformulas(r, columns);
+        }
+    }
+    let text = f.to_string();
+    if !columns.iter().any(|c| c.to_string() == text) {
+        columns.push(f.clone());
+    }
+}
+
+/// `1` for true and `0` for false under each header, the rows where the
+/// formula is true end with `*`
+impl fmt::Display for TruthTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let headers = self.headers();
+        writeln!(f, "{}", headers.join(" | "))?;
+        let widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        writeln!(f, "{}", rule.join("-+-"))?;
+        for (r, row) in self.rows.iter().enumerate() {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(&value, &w)| format!("{:<w$}", u8::from(value)))
+                .collect();
+            let line = cells.join(" | ");
+            if self.value(r) {
+                writeln!(f, "{line}  *")?;
+            } else {
+                writeln!(f, "{}", line.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
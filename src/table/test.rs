@@ -0,0 +1,54 @@
+use super::{TooLarge, TruthTable, LIMIT, MAX_LIMIT};
+use crate::testutil::formula;
+
+#[test]
+fn test_table() {
+    let table = TruthTable::new(&formula("(a => b) & ~(a => b)"), LIMIT).unwrap();
+    assert_eq!(
+        table.headers(),
+        [
+            "a",
+            "b",
+            "(a => b)",
+            "(~(a => b))",
+            "((a => b) & (~(a => b)))"
+        ]
+    );
+    assert_eq!(table.rows().len(), 4);
+    assert!(table.true_rows().is_empty());
+
+    let table = TruthTable::new(&formula("a | ~b"), LIMIT).unwrap();
+    assert_eq!(table.true_rows(), [0, 2, 3]);
+    assert_eq!(
+        table.lines(),
+        [
+            "a | b | (~b) | (a | (~b))",
+            "--+---+------+-----------",
+            "0 | 0 | 1    | 1           *",
+            "0 | 1 | 0    | 0",
+            "1 | 0 | 1    | 1           *",
+            "1 | 1 | 0    | 1           *",
+        ]
+    );
+}
+
+#[test]
+fn test_table_limit() {
+    let f = formula("a & b & c");
+    assert_eq!(TruthTable::new(&f, 3).unwrap().rows().len(), 8);
+    let err = TruthTable::new(&f, 2).unwrap_err();
+    assert_eq!(err, TooLarge { atoms: 3, limit: 2 });
+    assert_eq!(err.to_string(), "3 atoms, more than the limit of 2");
+
+    // a greater limit is `MAX_LIMIT`
+    let atoms: Vec<String> = (0..=MAX_LIMIT).map(|i| format!("x{i}")).collect();
+    let f = formula(&atoms.join(" & "));
+    let err = TruthTable::new(&f, usize::MAX).unwrap_err();
+    assert_eq!(
+        err,
+        TooLarge {
+            atoms: MAX_LIMIT + 1,
+            limit: MAX_LIMIT
+        }
+    );
+}
@@ -0,0 +1,299 @@
+use crate::ast::Formula;
+use crate::model::Model;
+use crate::symbol::Symbol;
+use crate::token::Kind;
+use std::collections::HashMap;
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// a formula of the tableau, numbered in the order it was added
+#[derive(Debug, Clone)]
+pub struct Entry {
+    number: usize,
+    formula: Formula,
+    /// the number of the formula it was found from, None for the input
+    from: Option<usize>,
+}
+
+impl Entry {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+    pub fn formula(&self) -> &Formula {
+        &self.formula
+    }
+    pub fn from(&self) -> Option<usize> {
+        self.from
+    }
+}
+
+/// how a branch ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum End {
+    /// the numbers of an atom and its negation
+    Closed(usize, usize),
+    /// every formula has been expanded without a contradiction
+    Open,
+}
+
+/// the formulas added by a rule, then either the end of the branch or the
+/// branches of the next rule
+#[derive(Debug, Clone)]
+pub struct Node {
+    entries: Vec<Entry>,
+    end: Option<End>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+    /// None if the branch goes on in the children
+    pub fn end(&self) -> Option<End> {
+        self.end
+    }
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+    /// true if every branch below the node is closed
+    pub fn is_closed(&self) -> bool {
+        match self.end {
+            Some(end) => end != End::Open,
+            None => self.children.iter().all(|c| c.is_closed()),
+        }
+    }
+}
+
+/// what a rule does with a formula
+enum Rule {
+    /// the formula is an atom (true if negated)
+    Literal(Symbol, bool),
+    /// the formulas are added to the branch
+    Alpha(Vec<Formula>),
+    /// the branch is split, each one with its formulas
+    Beta(Vec<Vec<Formula>>),
+}
+
+fn not(f: &Formula) -> Formula {
+    Formula::new_unary(Kind::Not, f.clone())
+}
+
+fn rule(f: &Formula) -> Rule {
+    match f {
+        Formula::Leaf(leaf) => Rule::Literal(leaf.symbol(), false),
+        Formula::Unary(unary) => match unary.right() {
+            Formula::Leaf(leaf) => Rule::Literal(leaf.symbol(), true),
+            Formula::Unary(inner) => Rule::Alpha(vec![inner.right().clone()]),
+            Formula::Binary(inner) => {
+                let (l, operator, r) = inner.parts();
+                match operator {
+                    Kind::And => Rule::Beta(vec![vec![not(l)], vec![not(r)]]),
+                    Kind::Or => Rule::Alpha(vec![not(l), not(r)]),
+                    Kind::Implies => Rule::Alpha(vec![l.clone(), not(r)]),
+                    Kind::Equiv => {
+                        Rule::Beta(vec![vec![l.clone(), not(r)], vec![not(l), r.clone()]])
+                    }
+                    _ => unreachable!("not a binary operator: {operator:?}"),
+                }
+            }
+        },
+        Formula::Binary(binary) => {
+            let (l, operator, r) = binary.parts();
+            match operator {
+                Kind::And => Rule::Alpha(vec![l.clone(), r.clone()]),
+                Kind::Or => Rule::Beta(vec![vec![l.clone()], vec![r.clone()]]),
+                Kind::Implies => Rule::Beta(vec![vec![not(l)], vec![r.clone()]]),
+                Kind::Equiv => Rule::Beta(vec![vec![l.clone(), r.clone()], vec![not(l), not(r)]]),
+                _ => unreachable!("not a binary operator: {operator:?}"),
+            }
+        }
+    }
+}
+
+/// the formulas on a branch, from the root
+#[derive(Clone, Default)]
+struct Branch {
+    formulas: Vec<(usize, Formula)>,
+    expanded: Vec<bool>,
+    /// the number of each literal on the branch
+    literals: HashMap<(Symbol, bool), usize>,
+}
+
+impl Branch {
+    /// the first formula not expanded yet whose rule is `kind`
+    fn next(&self, kind: impl Fn(&Rule) -> bool) -> Option<usize> {
+        (0..self.formulas.len()).find(|&i| !self.expanded[i] && kind(&rule(&self.formulas[i].1)))
+    }
+}
+
+/// an analytic tableau of a set of formulas: they are satisfiable exactly when
+/// a branch is open. The rules that do not split the branch are applied first
+#[derive(Debug, Clone)]
+pub struct Tableau {
+    root: Node,
+    /// from the first open branch
+    model: Option<Model>,
+}
+
+impl Tableau {
+    pub fn new(formulas: &[Formula]) -> Tableau {
+        let mut builder = Builder {
+            next: 1,
+            model: None,
+            symbols: Vec::new(),
+        };
+        for s in formulas.iter().flat_map(|f| f.symbols()) {
+            if !builder.symbols.contains(&s) {
+                builder.symbols.push(s);
+            }
+        }
+        let mut branch = Branch::default();
+        let root = builder.node(&mut branch, formulas.to_vec(), None);
+        Tableau {
+            root,
+            model: builder.model,
+        }
+    }
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+    /// true if the formulas are unsatisfiable
+    pub fn is_closed(&self) -> bool {
+        self.root.is_closed()
+    }
+    /// the literals of the first open branch, the other symbols are false
+    pub fn model(&self) -> Option<&Model> {
+        self.model.as_ref()
+    }
+    /// the lines of the tree, see `Display`
+    pub fn lines(&self) -> Vec<String> {
+        self.to_string().lines().map(|l| l.to_string()).collect()
+    }
+}
+
+struct Builder {
+    next: usize,
+    model: Option<Model>,
+    /// the symbols of the input
+    symbols: Vec<Symbol>,
+}
+
+impl Builder {
+    /// a node with `formulas`, then the rest of the branch
+    fn node(&mut self, branch: &mut Branch, formulas: Vec<Formula>, from: Option<usize>) -> Node {
+        let mut node = Node {
+            entries: Vec::new(),
+            end: None,
+            children: Vec::new(),
+        };
+        if self.add(branch, &mut node, formulas, from) {
+            return node;
+        }
+        // the formulas that do not split the branch first
+        while let Some(i) = branch.next(|r| matches!(r, Rule::Alpha(_))) {
+            branch.expanded[i] = true;
+            let (number, formula) = branch.formulas[i].clone();
+            let Rule::Alpha(formulas) = rule(&formula) else {
+                unreachable!()
+            };
+            if self.add(branch, &mut node, formulas, Some(number)) {
+                return node;
+            }
+        }
+        match branch.next(|r| matches!(r, Rule::Beta(_))) {
+            Some(i) => {
+                branch.expanded[i] = true;
+                let (number, formula) = branch.formulas[i].clone();
+                let Rule::Beta(alternatives) = rule(&formula) else {
+                    unreachable!()
+                };
+                for formulas in alternatives {
+                    let mut branch = branch.clone();
+                    node.children
+                        .push(self.node(&mut branch, formulas, Some(number)));
+                }
+            }
+            None => {
+                node.end = Some(End::Open);
+                if self.model.is_none() {
+                    let mut model = Model::new();
+                    for &s in &self.symbols {
+                        model.set(s, false);
+                    }
+                    for &(s, negated) in branch.literals.keys() {
+                        model.set(s, !negated);
+                    }
+                    self.model = Some(model);
+                }
+            }
+        }
+        node
+    }
+
+    /// adds the formulas to the node and the branch, returns true if it closes
+    fn add(
+        &mut self,
+        branch: &mut Branch,
+        node: &mut Node,
+        formulas: Vec<Formula>,
+        from: Option<usize>,
+    ) -> bool {
+        for formula in formulas {
+            let number = self.next;
+            self.next += 1;
+            node.entries.push(Entry {
+                number,
+                formula: formula.clone(),
+                from,
+            });
+            if let Rule::Literal(s, negated) = rule(&formula) {
+                if let Some(&other) = branch.literals.get(&(s, !negated)) {
+                    node.end = Some(End::Closed(other, number));
+                    return true;
+                }
+                branch.literals.entry((s, negated)).or_insert(number);
+            }
+            branch.formulas.push((number, formula));
+            branch.expanded.push(false);
+        }
+        false
+    }
+}
+
+fn write_node(f: &mut fmt::Formatter, node: &Node, first: &str, rest: &str) -> fmt::Result {
+    for (k, entry) in node.entries.iter().enumerate() {
+        let prefix = if k == 0 { first } else { rest };
+        write!(f, "{prefix}{}. {}", entry.number, entry.formula)?;
+        match entry.from {
+            Some(from) => writeln!(f, "  ({from})")?,
+            None => writeln!(f)?,
+        }
+    }
+    match node.end {
+        Some(End::Closed(a, b)) => writeln!(f, "{rest}x closed: {a}, {b}")?,
+        Some(End::Open) => writeln!(f, "{rest}o open")?,
+        None => {}
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let (first, next) = if last { ("`- ", "   ") } else { ("|- ", "|  ") };
+        write_node(
+            f,
+            child,
+            &format!("{rest}{first}"),
+            &format!("{rest}{next}"),
+        )?;
+    }
+    Ok(())
+}
+
+/// the tree, one formula per line with the number of the one it comes from:
+/// the branches end with `x` if closed, `o` if open
+impl fmt::Display for Tableau {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_node(f, &self.root, "", "")
+    }
+}
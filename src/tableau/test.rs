@@ -0,0 +1,61 @@
+use super::{End, Tableau};
+use crate::context::Context;
+use crate::testutil::formulas;
+
+#[test]
+fn test_tableau() {
+    let tableau = Tableau::new(&formulas("(a | b) & ~a"));
+    assert!(!tableau.is_closed());
+    assert_eq!(tableau.model().unwrap().to_string(), "{~a, b}");
+    assert_eq!(
+        tableau.lines(),
+        [
+            "1. ((a | b) & (~a))",
+            "2. (a | b)  (1)",
+            "3. (~a)  (1)",
+            "|- 4. a  (2)",
+            "|  x closed: 3, 4",
+            "`- 5. b  (2)",
+            "   o open",
+        ]
+    );
+
+    let tableau = Tableau::new(&formulas("a => b; a; ~b"));
+    assert!(tableau.is_closed());
+    assert!(tableau.model().is_none());
+    let children = tableau.root().children();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].end(), Some(End::Closed(2, 4)));
+    assert_eq!(children[1].end(), Some(End::Closed(3, 5)));
+}
+
+/// the tableau is closed exactly when resolution finds the box, and the model
+/// of an open branch is a model
+#[test]
+fn test_tableau_resolution() {
+    let tests = [
+        "a <=> b; ~(b <=> a)",
+        "~(a => (b => a))",
+        "(a | b) & (~a | c) & (~b | c) & ~c",
+        "~(a & b) <=> (~a | ~b)",
+        "(a <=> b) | (b <=> c) | (a <=> c); ~a | ~b; a | b",
+        "~((a => b) => ((b => c) => (a => c)))",
+        "~~a & (a => ~a)",
+    ];
+    for buffer in tests {
+        let formulas = formulas(buffer);
+        let tableau = Tableau::new(&formulas);
+        let mut context = Context::new();
+        for f in &formulas {
+            context.push(std::sync::Arc::new(f.clone())).unwrap();
+        }
+        assert_eq!(tableau.is_closed(), context.solve(), "{buffer}");
+        if let Some(model) = tableau.model() {
+            let solver = context.solver();
+            let clauses = solver.clauses();
+            for id in clauses.ids().filter(|&id| clauses.parents(id).is_none()) {
+                assert!(model.satisfies(clauses.clause(id)), "{buffer}: {model}");
+            }
+        }
+    }
+}
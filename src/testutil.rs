@@ -0,0 +1,81 @@
+//! helpers shared by the tests of the modules
+
+use crate::ast::Formula;
+use crate::clause::{Clause, Lit, SetClauses};
+use crate::context::Context;
+use crate::parser::Parser;
+use crate::parser::Statement;
+use crate::symbol::Symbol;
+
+/// a linear congruential generator, so that the random tests are repeatable
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random(seed)
+    }
+    /// a number in `0..n`
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+}
+
+/// the formulas of `buffer`, which has only formulas
+pub fn formulas(buffer: &str) -> Vec<Formula> {
+    let mut pars = Parser::new();
+    pars.load_bytes(buffer.to_string());
+    let context = Context::new();
+    let mut formulas = Vec::new();
+    loop {
+        match pars.parse_statement(&context).unwrap() {
+            Statement::Formula(f) => formulas.push(f),
+            Statement::Eoi => return formulas,
+            s => panic!("{s}"),
+        }
+    }
+}
+
+/// the only formula of `buffer`
+pub fn formula(buffer: &str) -> Formula {
+    let mut formulas = formulas(buffer);
+    assert_eq!(formulas.len(), 1, "{buffer}");
+    formulas.remove(0)
+}
+
+/// `clauses` like "a ~b, c": clauses separated by commas
+pub fn set(clauses: &str) -> SetClauses {
+    let mut set = SetClauses::default();
+    for c in clauses.split(',') {
+        let mut clause = Clause::new();
+        for x in c.split_whitespace() {
+            clause.insert(match x.strip_prefix('~') {
+                Some(x) => Lit::negative(Symbol::intern(x)),
+                None => Lit::positive(Symbol::intern(x)),
+            });
+        }
+        set.insert(&clause);
+    }
+    set
+}
+
+/// 4 to 11 clauses of 1 to 3 literals over the atoms `{prefix}0` to `{prefix}4`
+pub fn random_set(random: &mut Random, prefix: &str) -> SetClauses {
+    let mut set = SetClauses::default();
+    for _ in 0..4 + random.below(8) {
+        let mut c = Clause::new();
+        for _ in 0..1 + random.below(3) {
+            let s = Symbol::intern(&format!("{prefix}{}", random.below(5)));
+            c.insert(if random.below(2) == 0 {
+                Lit::positive(s)
+            } else {
+                Lit::negative(s)
+            });
+        }
+        set.insert(&c);
+    }
+    set
+}
@@ -1,4 +1,4 @@
-use std::{fmt, rc::Rc};
+use std::{fmt, sync::Arc};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Kind {
@@ -19,6 +19,18 @@ pub enum Kind {
     Minus,
     Exit,
     Help,
+    Tableau,
+    Deduce,
+    Sequent,
+    Table,
+    Nnf,
+    Cnf,
+    Dnf,
+    Minimize,
+    Simplify,
+    Comma,
+    Turnstile,
+    Greater,
 }
 
 impl Kind {
@@ -55,9 +67,51 @@ impl Kind {
             Kind::Minus => "-",
             Kind::Exit => "EXIT",
             Kind::Help => "HELP",
+            Kind::Tableau => "TABLEAU",
+            Kind::Deduce => "DEDUCE",
+            Kind::Sequent => "SEQUENT",
+            Kind::Table => "TABLE",
+            Kind::Nnf => "NNF",
+            Kind::Cnf => "CNF",
+            Kind::Dnf => "DNF",
+            Kind::Minimize => "MINIMIZE",
+            Kind::Simplify => "SIMPLIFY",
+            Kind::Comma => ",",
+            Kind::Turnstile => "|-",
+            Kind::Greater => ">",
         }
     }
 
+    /// the kind of the keyword `s`, None if it is an identifier
+    pub fn keyword(s: &str) -> Option<Kind> {
+        match s {
+            "exit" => Some(Kind::Exit),
+            "help" => Some(Kind::Help),
+            _ => None,
+        }
+    }
+
+    /// the kind of the command `s`: it is an identifier for the lexer, the
+    /// parser reads it as a command only at the start of a statement
+    pub fn command(s: &str) -> Option<Kind> {
+        match s {
+            "tableau" => Some(Kind::Tableau),
+            "deduce" => Some(Kind::Deduce),
+            "sequent" => Some(Kind::Sequent),
+            "table" => Some(Kind::Table),
+            "nnf" => Some(Kind::Nnf),
+            "cnf" => Some(Kind::Cnf),
+            "dnf" => Some(Kind::Dnf),
+            "minimize" => Some(Kind::Minimize),
+            "simplify" => Some(Kind::Simplify),
+            _ => None,
+        }
+    }
+
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Kind::And | Kind::Or | Kind::Implies | Kind::Equiv)
+    }
+
     pub fn is_sep(&self) -> bool {
         *self == Kind::Separator || *self == Kind::Eoi
     }
@@ -69,10 +123,68 @@ impl fmt::Display for Kind {
     }
 }
 
+/// rows and columns start from 1
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Default)]
+pub struct Position {
+    row: usize,
+    col: usize,
+}
+
+impl Position {
+    pub fn new(row: usize, col: usize) -> Position {
+        Position { row, col }
+    }
+    pub fn row(&self) -> usize {
+        self.row
+    }
+    pub fn col(&self) -> usize {
+        self.col
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.row, self.col)
+    }
+}
+
+/// `end` is the first position after the span
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Span {
+        Span { start, end }
+    }
+    /// `len` columns starting from `row:col`
+    pub fn on_row(row: usize, col: usize, len: usize) -> Span {
+        Span::new(Position::new(row, col), Position::new(row, col + len))
+    }
+    /// the smallest span containing both, an empty span is ignored
+    pub fn join(self, other: Span) -> Span {
+        if self == Span::default() {
+            other
+        } else if other == Span::default() {
+            self
+        } else {
+            Span::new(self.start.min(other.start), self.end.max(other.end))
+        }
+    }
+    pub fn start(&self) -> Position {
+        self.start
+    }
+    pub fn end(&self) -> Position {
+        self.end
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Token {
     kind: Kind,
-    literal: Rc<str>,
+    literal: Arc<str>,
     row: usize,
     col: usize,
 }
@@ -84,7 +196,7 @@ impl fmt::Display for Token {
 }
 
 impl Token {
-    pub fn new(kind: Kind, literal: Rc<str>, row: usize, col: usize) -> Token {
+    pub fn new(kind: Kind, literal: Arc<str>, row: usize, col: usize) -> Token {
         Token {
             kind,
             literal,
@@ -95,8 +207,8 @@ impl Token {
     pub fn kind(&self) -> Kind {
         self.kind
     }
-    pub fn literal(&self) -> Rc<str> {
-        Rc::clone(&self.literal)
+    pub fn literal(&self) -> Arc<str> {
+        Arc::clone(&self.literal)
     }
     pub fn row(&self) -> usize {
         self.row
@@ -104,6 +216,9 @@ impl Token {
     pub fn col(&self) -> usize {
         self.col
     }
+    pub fn span(&self) -> Span {
+        Span::on_row(self.row, self.col, self.literal.len())
+    }
     pub fn precedence(&self) -> usize {
         self.kind.precedence()
     }